@@ -0,0 +1,51 @@
+## API responses
+
+webhook-sent = Webhook sent successfully
+webhook-status-error = Error sending the webhook: { $status }
+webhook-error = Error sending the webhook
+invalid-data = Invalid benchmark data
+rate-limit-exceeded = Rate limit exceeded. Please try again later.
+
+## Validation
+
+must-be-positive-cpus = must be greater than 0
+must-be-positive-number = must be a positive number
+score-required = at least one score is needed
+
+## Signature
+
+signature-missing = Unsigned submission
+signature-malformed = Invalid { $header } header
+signature-unknown-key = Unknown signing key
+signature-expired = Expired signature
+signature-replayed = Repeated submission
+signature-invalid = Invalid signature
+
+## Discord embed
+
+embed-title = 📢 New Benchmark! 🔥
+embed-processor = 🔹 Processor
+embed-logical-cores = 🔹 Logical cores
+embed-frequency = 🔹 Frequency
+embed-vendor = 🔹 Vendor
+embed-microarchitecture = 🔹 Microarchitecture
+embed-os = 💻 Operating System
+embed-environment = ☁️ Environment
+embed-host-name = 👩‍💻👨‍💻 Host name
+embed-single-thread-score = 🔸 Single-thread Score
+embed-multi-thread-score = 🔸 Multi-thread Score
+embed-crypto-score = 🔐 Cryptography Score
+embed-compression-score = 🗜️ Compression Score
+embed-integer-score = 🧮 Integer Score
+embed-numeric-score = 🌌 Numeric Score
+embed-text-score = 📝 Text Processing Score
+embed-allocator-score = 🧱 Allocator Score
+embed-async-runtime-score = 🔀 Async Runtime Score
+embed-composite-score = 🏆 Composite Score
+embed-profile = 📋 Profile
+embed-footer = Benchmark made with love
+unknown = Unknown
+unknown-feminine = Unknown
+anonymous = Anonymous
+native = Native
+effective-cpus = { $cpus } effective CPUs
@@ -0,0 +1,51 @@
+## Respuestas de la API
+
+webhook-sent = Webhook enviado correctamente
+webhook-status-error = Error al enviar el webhook: { $status }
+webhook-error = Error al enviar el webhook
+invalid-data = Datos del benchmark inválidos
+rate-limit-exceeded = Rate limit excedido. Por favor, inténtalo más tarde.
+
+## Validación
+
+must-be-positive-cpus = debe ser mayor que 0
+must-be-positive-number = debe ser un número positivo
+score-required = se necesita al menos un score
+
+## Firma
+
+signature-missing = Envío sin firmar
+signature-malformed = Cabecera { $header } inválida
+signature-unknown-key = Clave de firma desconocida
+signature-expired = Firma caducada
+signature-replayed = Envío repetido
+signature-invalid = Firma inválida
+
+## Embed de Discord
+
+embed-title = 📢 ¡Nuevo Benchmark! 🔥
+embed-processor = 🔹 Procesador
+embed-logical-cores = 🔹 Núcleos lógicos
+embed-frequency = 🔹 Frecuencia
+embed-vendor = 🔹 Proveedor
+embed-microarchitecture = 🔹 Microarquitectura
+embed-os = 💻 Sistema Operativo
+embed-environment = ☁️ Entorno
+embed-host-name = 👩‍💻👨‍💻 Nombre de host
+embed-single-thread-score = 🔸 Single-thread Score
+embed-multi-thread-score = 🔸 Multi-thread Score
+embed-crypto-score = 🔐 Score de criptografía
+embed-compression-score = 🗜️ Score de compresión
+embed-integer-score = 🧮 Score de enteros
+embed-numeric-score = 🌌 Score numérico
+embed-text-score = 📝 Score de procesamiento de texto
+embed-allocator-score = 🧱 Score del asignador de memoria
+embed-async-runtime-score = 🔀 Score del runtime asíncrono
+embed-composite-score = 🏆 Score compuesto
+embed-profile = 📋 Perfil
+embed-footer = Benchmark realizado con cariño
+unknown = Desconocido
+unknown-feminine = Desconocida
+anonymous = Anónimo
+native = Nativo
+effective-cpus = { $cpus } CPUs efectivos
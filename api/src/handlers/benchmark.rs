@@ -1,32 +1,100 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
-use reqwest;
 use serde_json::json;
 
-use crate::models::CpuInfo;
+use crate::i18n::{self, t};
+use crate::models::{CpuInfo, FieldError};
+use crate::signature::SignatureVerifier;
+
+fn validation_error_response(message: &str, field_errors: &[FieldError]) -> HttpResponse {
+    HttpResponse::BadRequest()
+        .content_type("application/json")
+        .json(json!({
+            "success": false,
+            "error": message,
+            "field_errors": field_errors,
+            "timestamp": Utc::now().to_rfc3339()
+        }))
+}
+
+/// Extracts the field name from serde messages like "missing field `cpu_brand`", type
+/// errors don't name the field so they are reported against the whole body.
+fn field_from_serde_message(message: &str) -> &str {
+    message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split_once('`'))
+        .map(|(field, _)| field)
+        .unwrap_or("body")
+}
 
 pub async fn submit_tests(
-    info: web::Json<CpuInfo>,
+    req: HttpRequest,
+    body: web::Bytes,
     webhook_url: web::Data<String>,
+    signature_verifier: web::Data<SignatureVerifier>,
 ) -> impl Responder {
+    // Responses follow the client's Accept-Language, the embed uses the server locale
+    let locale = i18n::request_locale(req.headers());
+    let embed_locale = i18n::default_locale();
+
+    // The signature covers the raw body, so it is checked before deserializing
+    if let Err(e) = signature_verifier.verify(req.headers(), &body) {
+        return HttpResponse::Unauthorized()
+            .content_type("application/json")
+            .json(json!({
+                "success": false,
+                "error": e.message(&locale),
+                "timestamp": Utc::now().to_rfc3339()
+            }));
+    }
+
+    let info: CpuInfo = match serde_json::from_slice(&body) {
+        Ok(info) => info,
+        Err(e) => {
+            let field_errors = [FieldError::new(
+                field_from_serde_message(&e.to_string()),
+                e.to_string(),
+            )];
+            return validation_error_response(&t!(&locale, "invalid-data"), &field_errors);
+        }
+    };
+
+    let field_errors = info.validate(&locale);
+    if !field_errors.is_empty() {
+        return validation_error_response(&t!(&locale, "invalid-data"), &field_errors);
+    }
+
     let timestamp = Utc::now().to_rfc3339();
 
+    let unknown = t!(&embed_locale, "unknown");
+    let unknown_feminine = t!(&embed_locale, "unknown-feminine");
     let payload = json!({
         "embeds": [{
-            "title": "📢 ¡Nuevo Benchmark! 🔥",
+            "title": t!(&embed_locale, "embed-title"),
             "color": 1127128,
             "fields": [
-                {"name": "🔹 Procesador", "value": info.cpu_brand, "inline": false},
-                {"name": "🔹 Núcleos lógicos", "value": info.number_of_cpus, "inline": true},
-                {"name": "🔹 Frecuencia", "value": format!("{} MHz", info.cpu_frequency), "inline": true},
-                {"name": "🔹 Proveedor", "value": info.cpu_vendor_id, "inline": true},
-                {"name": "💻 Sistema Operativo", "value": info.system_info, "inline": false},
-                {"name": "👩‍💻👨‍💻 Nombre de host", "value": info.system_host_name, "inline": true},
-                {"name": "🔸 Single-thread Score", "value": info.score_single_thread.to_string(), "inline": true},
-                {"name": "🔸 Multi-thread Score", "value": info.score_multi_thread.to_string(), "inline": true},
+                {"name": t!(&embed_locale, "embed-processor"), "value": info.cpu_brand.as_deref().unwrap_or(&unknown), "inline": false},
+                {"name": t!(&embed_locale, "embed-logical-cores"), "value": info.number_of_cpus, "inline": true},
+                {"name": t!(&embed_locale, "embed-frequency"), "value": info.cpu_frequency.map(|mhz| format!("{} MHz", mhz)).unwrap_or(unknown_feminine.clone()), "inline": true},
+                {"name": t!(&embed_locale, "embed-vendor"), "value": info.cpu_vendor_id.as_deref().unwrap_or(&unknown), "inline": true},
+                {"name": t!(&embed_locale, "embed-microarchitecture"), "value": info.cpu_microarchitecture.as_deref().unwrap_or(&unknown_feminine), "inline": true},
+                {"name": t!(&embed_locale, "embed-os"), "value": info.system_info.as_deref().unwrap_or(&unknown), "inline": false},
+                {"name": t!(&embed_locale, "embed-environment"), "value": info.environment_summary(&embed_locale), "inline": true},
+                {"name": t!(&embed_locale, "embed-host-name"), "value": info.system_host_name.clone().unwrap_or_else(|| t!(&embed_locale, "anonymous")), "inline": true},
+                {"name": t!(&embed_locale, "embed-single-thread-score"), "value": info.score_single_thread.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-multi-thread-score"), "value": info.score_multi_thread.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-crypto-score"), "value": info.score_crypto.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-compression-score"), "value": info.score_compression.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-integer-score"), "value": info.score_integer.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-numeric-score"), "value": info.score_numeric.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-text-score"), "value": info.score_text.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-allocator-score"), "value": info.score_allocator.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-async-runtime-score"), "value": info.score_async_runtime.as_deref().unwrap_or("N/A"), "inline": true},
+                {"name": t!(&embed_locale, "embed-composite-score"), "value": info.composite_summary().unwrap_or("N/A".to_string()), "inline": true},
+                {"name": t!(&embed_locale, "embed-profile"), "value": info.profile_summary().unwrap_or("N/A".to_string()), "inline": true},
             ],
             "footer": {
-                "text": "Benchmark realizado con cariño",
+                "text": t!(&embed_locale, "embed-footer"),
                 "icon_url": "https://avatars.githubusercontent.com/u/74681819?s=280&v=4",
             },
             "timestamp": timestamp,
@@ -44,14 +112,14 @@ pub async fn submit_tests(
             .content_type("application/json")
             .json(json!({
                 "success": true,
-                "message": "Webhook enviado correctamente",
+                "message": t!(&locale, "webhook-sent"),
                 "timestamp": Utc::now().to_rfc3339()
             })),
         Ok(response) => HttpResponse::InternalServerError()
             .content_type("application/json")
             .json(json!({
                 "success": false,
-                "error": format!("Error al enviar el webhook: {}", response.status()),
+                "error": t!(&locale, "webhook-status-error", status = response.status().to_string()),
                 "timestamp": Utc::now().to_rfc3339()
             })),
         Err(e) => {
@@ -60,7 +128,7 @@ pub async fn submit_tests(
                 .content_type("application/json")
                 .json(json!({
                     "success": false,
-                    "error": "Error al enviar el webhook",
+                    "error": t!(&locale, "webhook-error"),
                     "timestamp": Utc::now().to_rfc3339()
                 }))
         }
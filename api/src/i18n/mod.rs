@@ -0,0 +1,101 @@
+use actix_web::http::header::{HeaderMap, ACCEPT_LANGUAGE};
+use fluent_langneg::{accepted_languages, negotiate_languages, NegotiationStrategy};
+use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::{static_loader, LanguageIdentifier, Loader};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Message catalogs in `locales/<language>/main.ftl`, embedded at build time
+static_loader! {
+    static LOCALES = {
+        locales: "./locales",
+        fallback_language: "es",
+        customise: |bundle| bundle.set_use_isolating(false),
+    };
+}
+
+const SUPPORTED_LANGUAGES: &[&str] = &["es", "en"];
+
+static DEFAULT_LOCALE: OnceLock<LanguageIdentifier> = OnceLock::new();
+
+fn supported_languages() -> Vec<LanguageIdentifier> {
+    SUPPORTED_LANGUAGES
+        .iter()
+        .filter_map(|language| language.parse().ok())
+        .collect()
+}
+
+/// Sets the locale of the Discord embeds and of the responses to clients that don't
+/// send `Accept-Language`. Unsupported values fall back to Spanish.
+pub fn init(default_locale: &str) -> LanguageIdentifier {
+    let requested = accepted_languages::parse(default_locale);
+    let available = supported_languages();
+    let locale = negotiate_languages(
+        &requested,
+        &available,
+        available.first(),
+        NegotiationStrategy::Lookup,
+    )[0]
+    .clone();
+    DEFAULT_LOCALE.get_or_init(|| locale).clone()
+}
+
+pub fn default_locale() -> LanguageIdentifier {
+    DEFAULT_LOCALE
+        .get_or_init(|| supported_languages().remove(0))
+        .clone()
+}
+
+/// Best supported match for the `Accept-Language` header of a request.
+pub fn request_locale(headers: &HeaderMap) -> LanguageIdentifier {
+    let default = default_locale();
+    let Some(header) = headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return default;
+    };
+
+    let requested = accepted_languages::parse(header);
+    let available = supported_languages();
+    negotiate_languages(
+        &requested,
+        &available,
+        Some(&default),
+        NegotiationStrategy::Lookup,
+    )[0]
+    .clone()
+}
+
+pub fn tr(locale: &LanguageIdentifier, id: &str) -> String {
+    LOCALES.lookup(locale, id)
+}
+
+pub fn tr_args(
+    locale: &LanguageIdentifier,
+    id: &str,
+    args: Vec<(&'static str, FluentValue<'static>)>,
+) -> String {
+    let args: HashMap<Cow<'static, str>, FluentValue> = args
+        .into_iter()
+        .map(|(name, value)| (Cow::Borrowed(name), value))
+        .collect();
+    LOCALES.lookup_with_args(locale, id, &args)
+}
+
+/// Looks up a message in the catalog of `locale`: `t!(&locale, "effective-cpus", cpus = 4)`.
+macro_rules! t {
+    ($locale:expr, $id:expr) => {
+        $crate::i18n::tr($locale, $id)
+    };
+    ($locale:expr, $id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args(
+            $locale,
+            $id,
+            vec![$((stringify!($name), ::fluent_templates::fluent_bundle::FluentValue::from($value))),+],
+        )
+    };
+}
+
+pub(crate) use t;
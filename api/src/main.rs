@@ -4,12 +4,15 @@ use actix_web::{web, App, HttpServer};
 use dotenv::dotenv;
 
 mod handlers;
+mod i18n;
 mod models;
 mod rate_limiter;
+mod signature;
 
 use handlers::{health_check, submit_tests};
-use log::debug;
+use log::{debug, warn};
 use rate_limiter::RateLimiterMiddleware;
+use signature::SignatureVerifier;
 use std::env;
 
 #[actix_web::main]
@@ -18,6 +21,18 @@ async fn main() -> std::io::Result<()> {
     dotenv().ok();
     let webhook_url =
         env::var("DISCORD_WEBHOOK_URL").expect("Error: DISCORD_WEBHOOK_URL is't set in .env file");
+    // Public keys (hex, comma separated) of the clients allowed to submit results,
+    // submissions must be signed within 5 minutes of the server clock
+    let signature_verifier = web::Data::new(
+        SignatureVerifier::from_hex_keys(&env::var("SIGNING_PUBLIC_KEYS").unwrap_or_default(), 300)
+            .expect("Error: SIGNING_PUBLIC_KEYS is not valid"),
+    );
+    if !signature_verifier.is_enabled() {
+        warn!("SIGNING_PUBLIC_KEYS is not set, submissions will not be verified");
+    }
+    // Locale of the Discord embeds and of the responses without Accept-Language
+    let default_locale = i18n::init(&env::var("DEFAULT_LOCALE").unwrap_or_default());
+    debug!("Default locale: {}", default_locale);
     // Rate limiter setting: 5 requests per hour
     let rate_limiter = RateLimiterMiddleware::new(5, 3600);
 
@@ -25,12 +40,22 @@ async fn main() -> std::io::Result<()> {
         debug!("Starting new server instance");
         App::new()
             .app_data(web::Data::new(webhook_url.clone())) // Inject webhook URL
+            .app_data(signature_verifier.clone())
+            // The latency matrix grows with the square of the CPUs, 1 MiB fits a few hundred
+            .app_data(web::PayloadConfig::new(1024 * 1024))
             .wrap(
                 Cors::default()
                     .allowed_origin("https://average-benchmark-api.rustlang-es.org")
                     .allowed_origin("http://average-benchmark-api.rustlang-es.org")
                     .allowed_methods(vec!["GET", "POST"])
-                    .allowed_headers(vec![header::CONTENT_TYPE])
+                    .allowed_headers(vec![
+                        header::CONTENT_TYPE,
+                        header::ACCEPT_LANGUAGE,
+                        header::HeaderName::from_static(signature::TIMESTAMP_HEADER),
+                        header::HeaderName::from_static(signature::NONCE_HEADER),
+                        header::HeaderName::from_static(signature::SIGNATURE_HEADER),
+                        header::HeaderName::from_static(signature::KEY_HEADER),
+                    ])
                     .max_age(3600),
             )
             // The /health route is outside the rate limiter because k8s need to use it for check status.
@@ -1,13 +1,172 @@
-use serde::Deserialize;
+use fluent_templates::LanguageIdentifier;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
 
 #[derive(Deserialize, Debug)]
 pub struct CpuInfo {
-    pub cpu_brand: String,
-    pub cpu_frequency: u32,
-    pub cpu_vendor_id: String,
+    #[serde(default)]
+    pub cpu_brand: Option<String>,
+    #[serde(default)]
+    pub cpu_frequency: Option<u32>,
+    #[serde(default)]
+    pub cpu_vendor_id: Option<String>,
+    #[serde(default)]
+    pub cpu_microarchitecture: Option<String>,
+    #[serde(default)]
+    pub hypervisor: Option<String>,
+    #[serde(default)]
+    pub container_runtime: Option<String>,
+    #[serde(default)]
+    pub effective_parallelism: Option<u32>,
+    #[serde(default)]
+    pub virtualized: bool,
+    #[serde(default)]
+    pub constrained: bool,
     pub number_of_cpus: u32,
-    pub score_multi_thread: String,
-    pub score_single_thread: String,
-    pub system_host_name: String,
-    pub system_info: String,
+    /// Missing when the workload was not selected on the client.
+    #[serde(default)]
+    pub score_multi_thread: Option<String>,
+    #[serde(default)]
+    pub score_single_thread: Option<String>,
+    /// Hashing and encryption throughput, missing in clients before the crypto workload.
+    #[serde(default)]
+    pub score_crypto: Option<String>,
+    /// LZ4, zstd and deflate throughput, missing in clients before the compression workload.
+    #[serde(default)]
+    pub score_compression: Option<String>,
+    /// Interpreter, map and sorting throughput, missing in clients before the integer
+    /// workload.
+    #[serde(default)]
+    pub score_integer: Option<String>,
+    /// n-body, FFT, SpMV and stencil throughput, missing in clients before the numeric
+    /// workload.
+    #[serde(default)]
+    pub score_numeric: Option<String>,
+    /// JSON, UTF-8, CSV and regex throughput, missing in clients before the text workload.
+    #[serde(default)]
+    pub score_text: Option<String>,
+    /// Allocation and free throughput, missing in clients before the allocator workload.
+    #[serde(default)]
+    pub score_allocator: Option<String>,
+    /// Tokio spawn, channel and loopback TCP throughput, missing in clients before the
+    /// async runtime workload.
+    #[serde(default)]
+    pub score_async_runtime: Option<String>,
+    /// Geometric mean of the workload scores, missing in clients before the scoring model.
+    #[serde(default)]
+    pub score_composite: Option<String>,
+    #[serde(default)]
+    pub scoring_model_version: Option<u32>,
+    /// Run profile (quick, standard or extended), only runs of the same profile and
+    /// version are comparable.
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profile_version: Option<u32>,
+    /// Missing when the client runs with the anonymous privacy level.
+    #[serde(default)]
+    pub system_host_name: Option<String>,
+    #[serde(default)]
+    pub system_info: Option<String>,
+}
+
+/// A field of the submitted payload that failed validation.
+#[derive(Serialize, Debug)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl CpuInfo {
+    /// Checks the values that deserialization alone can't reject, the messages are in
+    /// `locale`.
+    pub fn validate(&self, locale: &LanguageIdentifier) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.number_of_cpus == 0 {
+            errors.push(FieldError::new(
+                "number_of_cpus",
+                t!(locale, "must-be-positive-cpus"),
+            ));
+        }
+        for (field, score) in [
+            ("score_single_thread", &self.score_single_thread),
+            ("score_multi_thread", &self.score_multi_thread),
+            ("score_crypto", &self.score_crypto),
+            ("score_compression", &self.score_compression),
+            ("score_integer", &self.score_integer),
+            ("score_numeric", &self.score_numeric),
+            ("score_text", &self.score_text),
+            ("score_allocator", &self.score_allocator),
+            ("score_async_runtime", &self.score_async_runtime),
+            ("score_composite", &self.score_composite),
+        ] {
+            if let Some(score) = score {
+                match score.parse::<f64>() {
+                    Ok(value) if value.is_finite() && value >= 0.0 => {}
+                    _ => errors.push(FieldError::new(
+                        field,
+                        t!(locale, "must-be-positive-number"),
+                    )),
+                }
+            }
+        }
+        if self.score_single_thread.is_none() && self.score_multi_thread.is_none() {
+            errors.push(FieldError::new(
+                "score_single_thread",
+                t!(locale, "score-required"),
+            ));
+        }
+
+        errors
+    }
+
+    /// Composite score with the scoring model it was computed with, e.g. "1234.567 (v1)".
+    pub fn composite_summary(&self) -> Option<String> {
+        let score = self.score_composite.as_deref()?;
+        Some(match self.scoring_model_version {
+            Some(version) => format!("{} (v{})", score, version),
+            None => score.to_string(),
+        })
+    }
+
+    /// Profile with its version, e.g. "standard (v1)".
+    pub fn profile_summary(&self) -> Option<String> {
+        let profile = self.profile.as_deref()?;
+        Some(match self.profile_version {
+            Some(version) => format!("{} (v{})", profile, version),
+            None => profile.to_string(),
+        })
+    }
+
+    /// Short description of where the benchmark ran, e.g. "KVM, docker, 4 CPUs efectivos".
+    pub fn environment_summary(&self, locale: &LanguageIdentifier) -> String {
+        if !self.virtualized && !self.constrained {
+            return t!(locale, "native");
+        }
+
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(hypervisor) = &self.hypervisor {
+            parts.push(hypervisor.clone());
+        }
+        if let Some(runtime) = &self.container_runtime {
+            parts.push(runtime.clone());
+        }
+        if self.constrained {
+            if let Some(cpus) = self.effective_parallelism {
+                parts.push(t!(locale, "effective-cpus", cpus = cpus));
+            }
+        }
+        parts.join(", ")
+    }
 }
@@ -1,3 +1,3 @@
 mod cpu_info;
 
-pub use cpu_info::CpuInfo;
+pub use cpu_info::{CpuInfo, FieldError};
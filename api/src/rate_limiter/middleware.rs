@@ -1,36 +1,52 @@
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::RETRY_AFTER,
     Error, HttpResponse, ResponseError,
 };
 use chrono::Utc;
+use fluent_templates::LanguageIdentifier;
 use serde_json::json;
 use std::fmt;
 use std::future::{ready, Future, Ready};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use super::RateLimiter;
+use crate::i18n::{self, t};
 
 #[derive(Debug)]
-struct RateLimitError;
+struct RateLimitError {
+    retry_after: Duration,
+    // Language of the response body, taken from the request's Accept-Language
+    locale: LanguageIdentifier,
+}
 
 impl fmt::Display for RateLimitError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Rate limit excedido")
+        write!(f, "{}", t!(&self.locale, "rate-limit-exceeded"))
     }
 }
 
 impl ResponseError for RateLimitError {
     fn error_response(&self) -> HttpResponse {
+        // Round up so clients never retry a moment before the window frees up
+        let retry_after =
+            self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0);
+        let reset_at = Utc::now() + chrono::Duration::seconds(retry_after as i64);
+
         let json_error = json!({
             "success": false,
-            "error": "Rate limit excedido. Por favor, inténtalo más tarde.",
+            "error": t!(&self.locale, "rate-limit-exceeded"),
+            "retry_after": retry_after,
+            "reset_at": reset_at.to_rfc3339(),
             "timestamp": Utc::now().to_rfc3339()
         });
 
         HttpResponse::TooManyRequests()
             .content_type("application/json")
+            .insert_header((RETRY_AFTER, retry_after.to_string()))
             .json(json_error)
     }
 }
@@ -97,11 +113,17 @@ where
             .unwrap_or("unknown")
             .to_string();
 
-        if self.limiter.is_rate_limited(&ip) {
-            return Box::pin(async move { Err(RateLimitError.into()) });
+        if let Err(retry_after) = self.limiter.check(&ip) {
+            let locale = i18n::request_locale(req.headers());
+            return Box::pin(async move {
+                Err(RateLimitError {
+                    retry_after,
+                    locale,
+                }
+                .into())
+            });
         }
 
-        let fut = self.service.call(req);
-        Box::pin(async move { fut.await })
+        Box::pin(self.service.call(req))
     }
 }
@@ -22,7 +22,9 @@ impl RateLimiter {
         }
     }
 
-    pub fn is_rate_limited(&self, ip: &str) -> bool {
+    /// Records a request from `ip`. When the client is over the limit the request is not
+    /// recorded and the time until the oldest request leaves the window is returned.
+    pub fn check(&self, ip: &str) -> Result<(), Duration> {
         let now = Instant::now();
         let window = Duration::from_secs(self.window_size);
 
@@ -30,7 +32,7 @@ impl RateLimiter {
             Ok(guard) => guard,
             Err(poisoned) => {
                 eprintln!("Error al obtener el lock del Mutex: {:?}", poisoned);
-                return true; // no confiamos porque ha sido poisoned, así que lo limitamos
+                return Err(window); // no confiamos porque ha sido poisoned, así que lo limitamos
             }
         };
 
@@ -40,10 +42,11 @@ impl RateLimiter {
         timestamps.retain(|&timestamp| now.duration_since(timestamp) < window);
 
         if timestamps.len() >= self.max_requests {
-            return true;
+            let oldest = timestamps.iter().min().copied().unwrap_or(now);
+            return Err(window.saturating_sub(now.duration_since(oldest)));
         }
 
         timestamps.push(now);
-        false
+        Ok(())
     }
 }
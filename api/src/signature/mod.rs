@@ -0,0 +1,293 @@
+use actix_web::http::header::HeaderMap;
+use chrono::Utc;
+use ed25519_dalek::{Signature, VerifyingKey};
+use fluent_templates::LanguageIdentifier;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::i18n::{self, t};
+
+pub const TIMESTAMP_HEADER: &str = "x-benchmark-timestamp";
+pub const NONCE_HEADER: &str = "x-benchmark-nonce";
+pub const SIGNATURE_HEADER: &str = "x-benchmark-signature";
+pub const KEY_HEADER: &str = "x-benchmark-key";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    Missing,
+    Malformed(&'static str),
+    UnknownKey,
+    Expired,
+    Replayed,
+    Invalid,
+}
+
+impl SignatureError {
+    pub fn message(&self, locale: &LanguageIdentifier) -> String {
+        match self {
+            SignatureError::Missing => t!(locale, "signature-missing"),
+            SignatureError::Malformed(header) => {
+                t!(locale, "signature-malformed", header = *header)
+            }
+            SignatureError::UnknownKey => t!(locale, "signature-unknown-key"),
+            SignatureError::Expired => t!(locale, "signature-expired"),
+            SignatureError::Replayed => t!(locale, "signature-replayed"),
+            SignatureError::Invalid => t!(locale, "signature-invalid"),
+        }
+    }
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message(&i18n::default_locale()))
+    }
+}
+
+/// Verifies the ed25519 signature the client adds to each submission.
+///
+/// The signed message is `"{timestamp}\n{nonce}\n"` followed by the exact request body.
+/// The timestamp must be within `max_clock_skew` seconds of the server clock and each
+/// nonce is accepted only once inside that window, which prevents replays.
+///
+/// The private key ships inside every official client binary, so a valid signature
+/// only rules out casual forgery. It must not be treated as proof that the results
+/// are genuine.
+pub struct SignatureVerifier {
+    keys: Vec<VerifyingKey>,
+    max_clock_skew: i64,
+    // Nonce and the timestamp it was signed with
+    seen_nonces: Mutex<HashMap<String, i64>>,
+}
+
+impl SignatureVerifier {
+    pub fn new(keys: Vec<VerifyingKey>, max_clock_skew: i64) -> Self {
+        SignatureVerifier {
+            keys,
+            max_clock_skew,
+            seen_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Parses a comma separated list of hex encoded public keys.
+    pub fn from_hex_keys(keys: &str, max_clock_skew: i64) -> Result<Self, String> {
+        let keys = keys
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let mut bytes = [0u8; 32];
+                hex::decode_to_slice(key, &mut bytes)
+                    .map_err(|e| format!("Clave pública inválida {}: {}", key, e))?;
+                VerifyingKey::from_bytes(&bytes)
+                    .map_err(|e| format!("Clave pública inválida {}: {}", key, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(SignatureVerifier::new(keys, max_clock_skew))
+    }
+
+    /// Without keys every submission is accepted, which is only meant for development.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), SignatureError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let header = |name: &'static str| -> Result<&str, SignatureError> {
+            headers
+                .get(name)
+                .ok_or(SignatureError::Missing)?
+                .to_str()
+                .map_err(|_| SignatureError::Malformed(name))
+        };
+
+        let timestamp = header(TIMESTAMP_HEADER)?
+            .parse::<i64>()
+            .map_err(|_| SignatureError::Malformed(TIMESTAMP_HEADER))?;
+        let nonce = header(NONCE_HEADER)?;
+
+        let mut signature = [0u8; 64];
+        hex::decode_to_slice(header(SIGNATURE_HEADER)?, &mut signature)
+            .map_err(|_| SignatureError::Malformed(SIGNATURE_HEADER))?;
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(header(KEY_HEADER)?, &mut key)
+            .map_err(|_| SignatureError::Malformed(KEY_HEADER))?;
+
+        self.verify_at(
+            &key,
+            &Signature::from_bytes(&signature),
+            timestamp,
+            nonce,
+            body,
+            Utc::now().timestamp(),
+        )
+    }
+
+    /// Checks a signature against the clock value `now`, so the checks can be exercised
+    /// with local keys and fixed times.
+    pub fn verify_at(
+        &self,
+        key: &[u8; 32],
+        signature: &Signature,
+        timestamp: i64,
+        nonce: &str,
+        body: &[u8],
+        now: i64,
+    ) -> Result<(), SignatureError> {
+        let key = self
+            .keys
+            .iter()
+            .find(|known| known.as_bytes() == key)
+            .ok_or(SignatureError::UnknownKey)?;
+
+        // `abs_diff` can't overflow with timestamps far from the server clock
+        if now.abs_diff(timestamp) > self.max_clock_skew.unsigned_abs() {
+            return Err(SignatureError::Expired);
+        }
+        if nonce.is_empty() || nonce.len() > 64 {
+            return Err(SignatureError::Malformed(NONCE_HEADER));
+        }
+
+        let mut message = format!("{}\n{}\n", timestamp, nonce).into_bytes();
+        message.extend_from_slice(body);
+        key.verify_strict(&message, signature)
+            .map_err(|_| SignatureError::Invalid)?;
+
+        // The nonce is only recorded once the signature is valid, so unsigned requests
+        // can't fill the map.
+        let mut seen_nonces = match self.seen_nonces.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        seen_nonces
+            .retain(|_, signed_at| now.abs_diff(*signed_at) <= self.max_clock_skew.unsigned_abs());
+        if seen_nonces.contains_key(nonce) {
+            return Err(SignatureError::Replayed);
+        }
+        seen_nonces.insert(nonce.to_string(), timestamp);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const NOW: i64 = 1_700_000_000;
+    const SKEW: i64 = 300;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn sign(key: &SigningKey, timestamp: i64, nonce: &str, body: &[u8]) -> Signature {
+        let mut message = format!("{}\n{}\n", timestamp, nonce).into_bytes();
+        message.extend_from_slice(body);
+        key.sign(&message)
+    }
+
+    fn verifier() -> SignatureVerifier {
+        SignatureVerifier::new(vec![signing_key(1).verifying_key()], SKEW)
+    }
+
+    fn verify(
+        verifier: &SignatureVerifier,
+        key: &SigningKey,
+        timestamp: i64,
+        nonce: &str,
+        body: &[u8],
+    ) -> Result<(), SignatureError> {
+        let signature = sign(key, timestamp, nonce, body);
+        verifier.verify_at(
+            key.verifying_key().as_bytes(),
+            &signature,
+            timestamp,
+            nonce,
+            body,
+            NOW,
+        )
+    }
+
+    #[test]
+    fn accepts_a_valid_signature() {
+        assert_eq!(
+            verify(&verifier(), &signing_key(1), NOW, "a", b"{}"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_key() {
+        assert_eq!(
+            verify(&verifier(), &signing_key(2), NOW, "a", b"{}"),
+            Err(SignatureError::UnknownKey)
+        );
+    }
+
+    #[test]
+    fn rejects_a_signature_of_another_key() {
+        let signature = sign(&signing_key(2), NOW, "a", b"{}");
+        let key = signing_key(1).verifying_key();
+        assert_eq!(
+            verifier().verify_at(key.as_bytes(), &signature, NOW, "a", b"{}", NOW),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn rejects_a_tampered_body() {
+        let signature = sign(&signing_key(1), NOW, "a", b"{\"score\":1}");
+        let key = signing_key(1).verifying_key();
+        assert_eq!(
+            verifier().verify_at(key.as_bytes(), &signature, NOW, "a", b"{\"score\":2}", NOW),
+            Err(SignatureError::Invalid)
+        );
+    }
+
+    #[test]
+    fn checks_the_clock_skew_both_ways() {
+        let verifier = verifier();
+        let key = signing_key(1);
+        assert_eq!(verify(&verifier, &key, NOW - SKEW, "a", b"{}"), Ok(()));
+        assert_eq!(verify(&verifier, &key, NOW + SKEW, "b", b"{}"), Ok(()));
+        assert_eq!(
+            verify(&verifier, &key, NOW - SKEW - 1, "c", b"{}"),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            verify(&verifier, &key, NOW + SKEW + 1, "d", b"{}"),
+            Err(SignatureError::Expired)
+        );
+    }
+
+    #[test]
+    fn rejects_timestamps_that_would_overflow() {
+        let verifier = verifier();
+        let key = signing_key(1);
+        assert_eq!(
+            verify(&verifier, &key, i64::MIN, "a", b"{}"),
+            Err(SignatureError::Expired)
+        );
+        assert_eq!(
+            verify(&verifier, &key, i64::MAX, "b", b"{}"),
+            Err(SignatureError::Expired)
+        );
+    }
+
+    #[test]
+    fn rejects_a_replayed_nonce() {
+        let verifier = verifier();
+        let key = signing_key(1);
+        assert_eq!(verify(&verifier, &key, NOW, "a", b"{}"), Ok(()));
+        assert_eq!(
+            verify(&verifier, &key, NOW, "a", b"{}"),
+            Err(SignatureError::Replayed)
+        );
+        assert_eq!(verify(&verifier, &key, NOW, "b", b"{}"), Ok(()));
+    }
+}
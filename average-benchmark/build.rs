@@ -1,3 +1,8 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+use std::process::Command;
+
 fn main() {
     println!(
         "cargo::rustc-env=BACKEND_URL={}",
@@ -5,5 +10,65 @@ fn main() {
             .unwrap_or("http://average-benchmark-api.rustlang-es.org".to_string())
     );
 
+    let rustc = std::env::var("RUSTC").unwrap_or("rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or("unknown".to_string());
+
+    println!("cargo::rustc-env=BUILD_RUSTC_VERSION={}", rustc_version);
+    println!(
+        "cargo::rustc-env=BUILD_TARGET={}",
+        std::env::var("TARGET").unwrap_or("unknown".to_string())
+    );
+    println!(
+        "cargo::rustc-env=BUILD_PROFILE={}",
+        std::env::var("PROFILE").unwrap_or("unknown".to_string())
+    );
+
+    // Seed (hex) of the ed25519 release key used to sign submissions, only set in CI.
+    let signing_key = std::env::var("SIGNING_KEY").unwrap_or_default();
+    let out_dir = std::env::var("OUT_DIR").expect("cargo sets OUT_DIR");
+    std::fs::write(
+        Path::new(&out_dir).join("signing_key.rs"),
+        signing_key_source(signing_key.trim()),
+    )
+    .expect("failed to write signing_key.rs");
+
     println!("cargo:rerun-if-env-changed=BACKEND_URL");
+    println!("cargo:rerun-if-env-changed=SIGNING_KEY");
+}
+
+/// Source of the `RELEASE_SIGNING_KEY` constants. The seed is stored XORed with a mask
+/// drawn for every build, so it doesn't show up as a string in the binary. That only
+/// hides it from a casual look, anyone with the binary can recover it.
+fn signing_key_source(seed_hex: &str) -> String {
+    if seed_hex.is_empty() {
+        return "const RELEASE_SIGNING_KEY: Option<([u8; 32], [u8; 32])> = None;\n".to_string();
+    }
+    let seed: Vec<u8> = (0..seed_hex.len())
+        .step_by(2)
+        .map(|i| {
+            seed_hex
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<_>>()
+        .filter(|seed: &Vec<u8>| seed.len() == 32)
+        .expect("SIGNING_KEY must be the 32-byte seed in hex");
+
+    let mut mask = Vec::with_capacity(32);
+    while mask.len() < 32 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(mask.len());
+        mask.extend_from_slice(&hasher.finish().to_le_bytes());
+    }
+    let masked: Vec<u8> = seed.iter().zip(&mask).map(|(a, b)| a ^ b).collect();
+    format!(
+        "const RELEASE_SIGNING_KEY: Option<([u8; 32], [u8; 32])> = Some(({:?}, {:?}));\n",
+        masked, mask
+    )
 }
@@ -0,0 +1,112 @@
+#ifndef AVERAGE_BENCHMARK_H
+#define AVERAGE_BENCHMARK_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Version of the C ABI, bumped on any incompatible change of the structs or functions.
+#define AVERAGE_BENCHMARK_ABI_VERSION 1
+
+#define AVERAGE_BENCHMARK_PROFILE_QUICK 0
+
+#define AVERAGE_BENCHMARK_PROFILE_STANDARD 1
+
+#define AVERAGE_BENCHMARK_PROFILE_EXTENDED 2
+
+#define AVERAGE_BENCHMARK_WORKLOAD_SINGLE_THREAD (1 << 0)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_MULTI_THREAD (1 << 1)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_CRYPTO (1 << 2)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_COMPRESSION (1 << 3)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_INTEGER (1 << 4)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_LATENCY (1 << 5)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_NUMERIC (1 << 6)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_TEXT (1 << 7)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_ALLOCATOR (1 << 8)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_ASYNC_RUNTIME (1 << 9)
+
+#define AVERAGE_BENCHMARK_WORKLOAD_OS_OVERHEAD (1 << 10)
+
+// Opt-in, writes a scratch file to the temporary directory.
+#define AVERAGE_BENCHMARK_WORKLOAD_STORAGE (1 << 11)
+
+// Results of a run. Scores of workloads that did not run are NaN, the results of the
+// workloads without a field here are in `json`.
+typedef struct AverageBenchmarkResults {
+  uint32_t profile_version;
+  uint32_t scoring_model_version;
+  uint32_t threads;
+  double score_single_thread;
+  double score_multi_thread;
+  double score_composite;
+  uint64_t single_thread_duration_ns;
+  uint64_t multi_thread_duration_ns;
+  // The full report as a JSON document, owned by the results.
+  char *json;
+} AverageBenchmarkResults;
+
+// Options of a run. A NULL pointer runs the standard profile.
+typedef struct AverageBenchmarkOptions {
+  // One of the `AVERAGE_BENCHMARK_PROFILE_*` constants.
+  uint32_t profile;
+  // `AVERAGE_BENCHMARK_WORKLOAD_*` flags, 0 runs the workloads of the profile.
+  uint32_t workloads;
+  // Threads of the multi-thread workloads, 0 uses the effective parallelism.
+  uint32_t threads;
+} AverageBenchmarkOptions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t average_benchmark_abi_version(void);
+
+// Runs the suite and returns its results, to be released with
+// `average_benchmark_results_free`. Returns NULL if the options are invalid.
+//
+// # Safety
+//
+// `options` must be NULL or point to a valid `AverageBenchmarkOptions`.
+struct AverageBenchmarkResults *average_benchmark_run(const struct AverageBenchmarkOptions *options);
+
+// Releases results returned by `average_benchmark_run`. NULL is ignored.
+//
+// # Safety
+//
+// `results` must be NULL or a pointer returned by `average_benchmark_run` that was not
+// released yet.
+void average_benchmark_results_free(struct AverageBenchmarkResults *results);
+
+// Runs the suite and returns the report as a JSON document, to be released with
+// `average_benchmark_string_free`. Returns NULL if the options are invalid.
+//
+// # Safety
+//
+// `options` must be NULL or point to a valid `AverageBenchmarkOptions`.
+char *average_benchmark_run_json(const struct AverageBenchmarkOptions *options);
+
+// Releases a string returned by the library. NULL is ignored.
+//
+// # Safety
+//
+// `string` must be NULL or a string returned by `average_benchmark_run_json` that was
+// not released yet.
+void average_benchmark_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AVERAGE_BENCHMARK_H */
@@ -0,0 +1,69 @@
+/*
+ * Exercises the C ABI: runs the quick profile through both entry points and checks the
+ * results. Build and run it with `make -C c test` from the crate directory.
+ */
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "average_benchmark.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                     \
+    do {                                                                     \
+        if (!(condition)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                             \
+            failures++;                                                      \
+        }                                                                    \
+    } while (0)
+
+int main(void) {
+    CHECK(average_benchmark_abi_version() == AVERAGE_BENCHMARK_ABI_VERSION);
+
+    AverageBenchmarkOptions options = {
+        .profile = AVERAGE_BENCHMARK_PROFILE_QUICK,
+        .workloads = AVERAGE_BENCHMARK_WORKLOAD_SINGLE_THREAD,
+        .threads = 1,
+    };
+
+    AverageBenchmarkResults *results = average_benchmark_run(&options);
+    CHECK(results != NULL);
+    if (results != NULL) {
+        printf("single-thread score: %.3f (%llu ns)\n", results->score_single_thread,
+               (unsigned long long)results->single_thread_duration_ns);
+        CHECK(results->score_single_thread > 0.0);
+        CHECK(results->single_thread_duration_ns > 0);
+        CHECK(isnan(results->score_multi_thread));
+        CHECK(fabs(results->score_composite - results->score_single_thread) < 1e-6 * results->score_single_thread);
+        CHECK(results->profile_version >= 1);
+        CHECK(results->scoring_model_version >= 1);
+        CHECK(results->json != NULL && strstr(results->json, "\"profile\":\"quick\"") != NULL);
+        average_benchmark_results_free(results);
+    }
+
+    options.workloads = AVERAGE_BENCHMARK_WORKLOAD_MULTI_THREAD;
+    char *json = average_benchmark_run_json(&options);
+    CHECK(json != NULL);
+    if (json != NULL) {
+        printf("%s\n", json);
+        CHECK(strstr(json, "\"multi_thread\":{") != NULL);
+        CHECK(strstr(json, "\"single_thread\":null") != NULL);
+        average_benchmark_string_free(json);
+    }
+
+    options.profile = 42;
+    CHECK(average_benchmark_run(&options) == NULL);
+    CHECK(average_benchmark_run_json(&options) == NULL);
+
+    average_benchmark_results_free(NULL);
+    average_benchmark_string_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,143 @@
+## Consent and notices
+
+ask-to-send = Do you want to send the benchmark data to the server? (y/n, or always/never to remember the answer)
+ask-to-send-note = (Your information will only be used to publish a report on Discord and won't be stored in any database.)
+invalid-answer = Invalid input. Please type 'y' for yes, 'n' for no, 'always' to always send or 'never' to never send.
+read-answer-error = Error reading the input, the data won't be sent.
+stdin-not-terminal = There is no terminal to confirm the submission, the data won't be sent. Use --yes to send it.
+consent-saved = Answer saved in { $path }. You can change it with the `consent` option.
+payload-preview = 🔎 This is the exact data that will be sent:
+privacy-policy-notice = ℹ️  This application will send information about your CPU and operating system to a Discord webhook.
+privacy-policy-details = 📜 You can review the details in the PRIVACY.md file in the repository.
+data-not-sent = Data not sent.
+
+## Results
+
+logical-cores = Logical cores numbers detected
+effective-parallelism = Effective parallelism (cgroup/cpuset limit)
+hypervisor = Hypervisor
+container-runtime = Container runtime
+profile = Profile { $name } (v{ $version })
+profile-settings = { $iterations } iterations, { $repetitions } repetitions
+telemetry-sample = Repetition { $repetition }/{ $repetitions }: { $duration } ms, { $frequency } MHz, { $temperature } °C
+scaling = Multi-thread scaling
+scaling-threads = { $threads ->
+    [one] 1 thread
+   *[other] { $threads } threads
+}
+single-thread-time = Single-thread time
+single-thread-result = Single-thread result
+single-thread-score = Single-thread score
+multi-thread-total-time = Multi-thread total time
+multi-thread-result = Multi-thread result
+multi-thread-score = Multi-thread score
+average-iterations-per-thread = Average iterations per thread
+min-thread-time = Min thread time
+composite-score = Composite score (model v{ $version }, reference = { $reference })
+max-thread-time = Max thread time
+thread = Thread { $thread }
+crypto = Cryptography
+crypto-throughput = { $algorithm }: { $throughput } MB/s (CPU features detected: { $features })
+crypto-no-cpu-features = none
+crypto-accelerated = accelerated path taken, { $speedup }x the { $software } MB/s of the software implementation
+crypto-not-accelerated = accelerated path not taken, { $speedup }x the { $software } MB/s of the software implementation
+crypto-no-software = no software implementation to compare with
+crypto-score = Cryptography score
+compression = Compression
+compression-throughput = { $codec } ({ $threads }): { $compress } MB/s compressing, { $decompress } MB/s decompressing, ratio { $ratio }
+workload-unscored = not scored, no valid measurement
+result-invalid = The result was wrong, it is left out of the score.
+compression-score = Compression score
+integer = Integer and data structures
+integer-throughput = { $kernel }: { $throughput } M/s
+integer-score = Integer score
+numeric = Numeric kernels (double precision)
+numeric-throughput = { $kernel } ({ $threads }): { $throughput } { $unit }, error { $error }
+numeric-score = Numeric score
+text = Text processing
+text-throughput = { $kernel }: { $throughput } MB/s
+text-score = Text processing score
+allocator = Allocator ({ $allocator })
+allocator-throughput = { $size } ({ $threads }): { $throughput } M allocations/s
+page-faults = First touch: { $nanoseconds } ns per 4 KiB page fault ({ $throughput } GB/s)
+page-faults-huge = With transparent huge pages: { $throughput } GB/s (×{ $speedup }, { $huge } MiB in huge pages)
+page-faults-no-huge = The kernel doesn't support transparent huge pages
+allocator-score = Allocator score
+async-runtime = Async runtime (tokio)
+async-workers = { $workers ->
+    [one] 1 worker
+   *[other] { $workers } workers
+}
+async-throughput = { $kernel }: { $throughput } { $unit }
+async-kernel-failed = { $kernel }: failed ({ $error })
+async-score = Async runtime score
+async-incomplete = not scored, a kernel failed
+os-overhead = Operating system overhead (ns)
+os-overhead-latency = { $kernel }: { $nanoseconds } ns
+os-overhead-unsupported = Only measured on Linux
+storage = Storage ({ $filesystem } on { $device })
+storage-sequential = Sequential: write { $write } MB/s, read { $read } MB/s
+storage-random = Random 4 KiB: read { $read } IOPS, write { $write } IOPS
+storage-no-direct-io = The filesystem doesn't support O_DIRECT, the random writes are not measured
+storage-fsync = fsync: median { $median } µs, p99 { $p99 } µs
+storage-error = Couldn't write the scratch file: { $error }
+latency = Core-to-core latency (ns)
+latency-single-cpu = More than one CPU is needed to measure the core-to-core latency
+latency-not-pinned = The threads couldn't be pinned to the CPUs, the latencies include migrations
+latency-summary = min { $min } ns, median { $median } ns, max { $max } ns
+contention-throughput = { $primitive } ({ $threads }): { $throughput } M op/s
+html-title = average-benchmark results
+html-system = System
+html-scores = Scores
+html-contention = Contention
+html-contention-invalid = wrong count, some increments were lost
+
+## Submission
+
+error = Error: { $error }
+submit-message = Message: { $message }
+submit-timestamp = Timestamp: { $timestamp }
+submit-success = Success
+unknown-error = Unknown error
+server-responded = The server responded { $status }
+retrying = Error sending ({ $reason }), retrying in { $wait } ({ $attempt }/{ $max })...
+retry-after-too-long = The server asks to wait { $wait }, more than the configured maximum.
+error-network = Could not connect to the server: { $error }
+error-rate-limited-until = { $message } (retry after { $reset })
+error-server = Server error ({ $status }): { $message }
+error-protocol = Unexpected server response ({ $status }): { $detail }
+error-signature = The server rejected the submission signature: { $message }
+invalid-signing-key = { $variable } is not a valid ed25519 key (32 bytes in hex).
+
+## Outbox and files
+
+no-pending-submissions = There are no pending submissions.
+sending-pending = Sending { $path }...
+pending-send-failed = Could not send { $path }: { $error }
+pending-rejected = The API rejected the submission, it was moved to { $path } and won't be sent again: { $error }
+pending-summary = Pending submissions: { $sent } sent, { $remaining } remaining, { $rejected } rejected.
+saved-to-outbox = Data saved to { $path }. You can send it later with `average-benchmark upload-pending`.
+payload-exported = Data exported to { $path }. You can send it with `average-benchmark submit { $path }`.
+no-data-dir = No data directory was found for the outbox.
+read-file-error = Could not read { $path }: { $error }
+write-file-error = Could not write { $path }: { $error }
+invalid-payload-file = { $path } doesn't contain a valid JSON payload: { $error }
+
+## Configuration and privacy
+
+config-read-error = Could not read the configuration { $path }: { $error }
+config-invalid = Invalid configuration file { $path }: { $error }
+field-required = The field `{ $field }` is needed to publish the result and can't be excluded.
+
+## System information
+
+command-empty-output = The command `{ $command }` with args { $args } returned empty output.
+command-error = Error running the command `{ $command }` with args { $args }: { $error }
+system-name-unavailable = The system name is not available.
+os-version-unavailable = The OS version is not available.
+log-system-info = System information: { $system }
+log-number-of-cpus = Number of CPUs: { $cpus }
+log-memory = Memory: { $total } MB total, { $available } MB available, swap { $swap_used }/{ $swap_total } MB
+log-container = Container: { $runtime }
+log-cgroup = Cgroup v{ $version }: quota { $quota } CPUs, cpuset { $cpuset } CPUs, effective parallelism { $parallelism }
+log-build = Built with { $rustc } for { $target } ({ $profile }, { $allocator } allocator)
@@ -0,0 +1,143 @@
+## Consentimiento y avisos
+
+ask-to-send = ¿Desea enviar los datos de la prueba al servidor? (y/n, o always/never para recordar la respuesta)
+ask-to-send-note = (Tu información solo se usará para generar un reporte en Discord y no se almacenará en ninguna base de datos.)
+invalid-answer = Entrada no válida. Por favor, escriba 'y' para sí, 'n' para no, 'always' para enviar siempre o 'never' para no enviar nunca.
+read-answer-error = Error al leer la entrada, los datos no se enviarán.
+stdin-not-terminal = No hay una terminal para confirmar el envío, los datos no se enviarán. Usa --yes para enviarlos.
+consent-saved = Respuesta guardada en { $path }. Puedes cambiarla con la opción `consent`.
+payload-preview = 🔎 Estos son los datos exactos que se enviarán:
+privacy-policy-notice = ℹ️  Esta aplicación enviará información sobre tu CPU y sistema operativo a un webhook de Discord.
+privacy-policy-details = 📜 Puedes revisar los detalles en el archivo PRIVACY.md en el repositorio.
+data-not-sent = Datos no enviados.
+
+## Resultados
+
+logical-cores = Núcleos lógicos detectados
+effective-parallelism = Paralelismo efectivo (límite de cgroup/cpuset)
+hypervisor = Hypervisor
+container-runtime = Runtime de contenedor
+profile = Perfil { $name } (v{ $version })
+profile-settings = { $iterations } iteraciones, { $repetitions } repeticiones
+telemetry-sample = Repetición { $repetition }/{ $repetitions }: { $duration } ms, { $frequency } MHz, { $temperature } °C
+scaling = Escalado multi-thread
+scaling-threads = { $threads ->
+    [one] 1 hilo
+   *[other] { $threads } hilos
+}
+single-thread-time = Tiempo single-thread
+single-thread-result = Resultado single-thread
+single-thread-score = Score single-thread
+multi-thread-total-time = Tiempo total multi-thread
+multi-thread-result = Resultado multi-thread
+multi-thread-score = Score multi-thread
+average-iterations-per-thread = Iteraciones promedio por hilo
+min-thread-time = Tiempo mínimo de hilo
+composite-score = Score compuesto (modelo v{ $version }, referencia = { $reference })
+max-thread-time = Tiempo máximo de hilo
+thread = Hilo { $thread }
+crypto = Criptografía
+crypto-throughput = { $algorithm }: { $throughput } MB/s (extensiones de CPU detectadas: { $features })
+crypto-no-cpu-features = ninguna
+crypto-accelerated = ruta acelerada usada, { $speedup }x los { $software } MB/s de la implementación por software
+crypto-not-accelerated = ruta acelerada no usada, { $speedup }x los { $software } MB/s de la implementación por software
+crypto-no-software = sin implementación por software con la que comparar
+crypto-score = Score de criptografía
+compression = Compresión
+compression-throughput = { $codec } ({ $threads }): { $compress } MB/s comprimiendo, { $decompress } MB/s descomprimiendo, ratio { $ratio }
+workload-unscored = sin score, ninguna medición válida
+result-invalid = El resultado fue incorrecto, queda fuera del score.
+compression-score = Score de compresión
+integer = Enteros y estructuras de datos
+integer-throughput = { $kernel }: { $throughput } M/s
+integer-score = Score de enteros
+numeric = Kernels numéricos (doble precisión)
+numeric-throughput = { $kernel } ({ $threads }): { $throughput } { $unit }, error { $error }
+numeric-score = Score numérico
+text = Procesamiento de texto
+text-throughput = { $kernel }: { $throughput } MB/s
+text-score = Score de procesamiento de texto
+allocator = Asignador de memoria ({ $allocator })
+allocator-throughput = { $size } ({ $threads }): { $throughput } M asignaciones/s
+page-faults = Primer acceso: { $nanoseconds } ns por fallo de página de 4 KiB ({ $throughput } GB/s)
+page-faults-huge = Con transparent huge pages: { $throughput } GB/s (×{ $speedup }, { $huge } MiB en huge pages)
+page-faults-no-huge = El kernel no soporta transparent huge pages
+allocator-score = Score del asignador de memoria
+async-runtime = Runtime asíncrono (tokio)
+async-workers = { $workers ->
+    [one] 1 worker
+   *[other] { $workers } workers
+}
+async-throughput = { $kernel }: { $throughput } { $unit }
+async-kernel-failed = { $kernel }: falló ({ $error })
+async-score = Score del runtime asíncrono
+async-incomplete = sin score, un kernel falló
+os-overhead = Sobrecarga del sistema operativo (ns)
+os-overhead-latency = { $kernel }: { $nanoseconds } ns
+os-overhead-unsupported = Solo se mide en Linux
+storage = Almacenamiento ({ $filesystem } en { $device })
+storage-sequential = Secuencial: escritura { $write } MB/s, lectura { $read } MB/s
+storage-random = Aleatorio de 4 KiB: lectura { $read } IOPS, escritura { $write } IOPS
+storage-no-direct-io = El sistema de archivos no soporta O_DIRECT, las escrituras aleatorias no se miden
+storage-fsync = fsync: mediana { $median } µs, p99 { $p99 } µs
+storage-error = No se pudo escribir el archivo temporal: { $error }
+latency = Latencia entre núcleos (ns)
+latency-single-cpu = Hace falta más de una CPU para medir la latencia entre núcleos
+latency-not-pinned = No se pudieron fijar los hilos a las CPUs, las latencias incluyen migraciones
+latency-summary = mín { $min } ns, mediana { $median } ns, máx { $max } ns
+contention-throughput = { $primitive } ({ $threads }): { $throughput } M op/s
+html-title = Resultados de average-benchmark
+html-system = Sistema
+html-scores = Scores
+html-contention = Contención
+html-contention-invalid = cuenta incorrecta, se perdieron incrementos
+
+## Envío
+
+error = Error: { $error }
+submit-message = Mensaje: { $message }
+submit-timestamp = Fecha: { $timestamp }
+submit-success = Éxito
+unknown-error = Error desconocido
+server-responded = El servidor respondió { $status }
+retrying = Error al enviar ({ $reason }), reintentando en { $wait } ({ $attempt }/{ $max })...
+retry-after-too-long = El servidor pide esperar { $wait }, más que el máximo configurado.
+error-network = No se pudo conectar con el servidor: { $error }
+error-rate-limited-until = { $message } (se puede reintentar a partir de { $reset })
+error-server = Error del servidor ({ $status }): { $message }
+error-protocol = Respuesta inesperada del servidor ({ $status }): { $detail }
+error-signature = El servidor rechazó la firma del envío: { $message }
+invalid-signing-key = { $variable } no es una clave ed25519 válida (32 bytes en hex).
+
+## Outbox y archivos
+
+no-pending-submissions = No hay envíos pendientes.
+sending-pending = Enviando { $path }...
+pending-send-failed = No se pudo enviar { $path }: { $error }
+pending-rejected = La API rechazó el envío, se movió a { $path } y no se volverá a enviar: { $error }
+pending-summary = Envíos pendientes: { $sent } enviados, { $remaining } restantes, { $rejected } rechazados.
+saved-to-outbox = Datos guardados en { $path }. Puedes enviarlos más tarde con `average-benchmark upload-pending`.
+payload-exported = Datos exportados a { $path }. Puedes enviarlos con `average-benchmark submit { $path }`.
+no-data-dir = No se encontró un directorio de datos para el outbox.
+read-file-error = No se pudo leer { $path }: { $error }
+write-file-error = No se pudo escribir { $path }: { $error }
+invalid-payload-file = { $path } no contiene un payload JSON válido: { $error }
+
+## Configuración y privacidad
+
+config-read-error = No se pudo leer la configuración { $path }: { $error }
+config-invalid = Archivo de configuración inválido { $path }: { $error }
+field-required = El campo `{ $field }` es necesario para publicar el resultado y no se puede excluir.
+
+## Información del sistema
+
+command-empty-output = El comando `{ $command }` con args { $args } devolvió salida vacía.
+command-error = Error al ejecutar el comando `{ $command }` con args { $args }: { $error }
+system-name-unavailable = El nombre del sistema no está disponible.
+os-version-unavailable = La versión del SO no está disponible.
+log-system-info = Información del sistema: { $system }
+log-number-of-cpus = Número de CPUs: { $cpus }
+log-memory = Memoria: { $total } MB totales, { $available } MB disponibles, swap { $swap_used }/{ $swap_total } MB
+log-container = Contenedor: { $runtime }
+log-cgroup = Cgroup v{ $version }: cuota { $quota } CPUs, cpuset { $cpuset } CPUs, paralelismo efectivo { $parallelism }
+log-build = Compilado con { $rustc } para { $target } ({ $profile }, asignador { $allocator })
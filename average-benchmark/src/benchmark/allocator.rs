@@ -0,0 +1,352 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use std::hint::black_box;
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::corpus;
+use crate::scoring;
+
+/// Global allocator of the `average-benchmark` binary of this build, chosen with the
+/// `mimalloc` and `jemalloc` features. Programs that use the library, or the C ABI,
+/// measure their own global allocator instead.
+pub const GLOBAL_ALLOCATOR: &str = if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else if cfg!(feature = "jemalloc") {
+    "jemalloc"
+} else {
+    "system"
+};
+
+// Allocations each thread keeps alive, every operation frees one at random and
+// allocates its replacement, so the heap is fragmented like the one of a long running
+// service. Large allocations keep fewer, 64 of up to 4 MiB already hold up to 256 MiB
+// per thread.
+const LIVE_ALLOCATIONS: usize = 4096;
+const LARGE_LIVE_ALLOCATIONS: usize = 64;
+
+// Slots and sizes are drawn outside of the measured time and cycled.
+const PLAN_LENGTH: usize = 1 << 16;
+
+// An allocation and a free take tens of nanoseconds, the small and mixed sizes do this
+// fraction of the profile's operations.
+const OPERATIONS_DIVISOR: u64 = 50;
+
+// Large allocations map and unmap memory and take microseconds, they do this fraction
+// of the operations of the other sizes.
+const LARGE_DIVISOR: u64 = 40;
+
+// Bytes written for the first time by the page fault test, with regular and with
+// transparent huge pages.
+#[cfg(target_os = "linux")]
+const PAGE_FAULT_BYTES: usize = 256 * 1024 * 1024;
+#[cfg(target_os = "linux")]
+const PAGE_SIZE: usize = 4096;
+#[cfg(target_os = "linux")]
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+#[cfg(target_os = "linux")]
+const PAGE_FAULT_RUNS: u32 = 3;
+
+/// Size classes of the allocator workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AllocationSize {
+    /// 16 to 256 bytes, like strings and small collections.
+    Small,
+    /// 16 bytes to 64 KiB with as many allocations of each power of two, like buffers.
+    Mixed,
+    /// 256 KiB to 4 MiB, usually mapped and unmapped by the allocator.
+    Large,
+}
+
+impl AllocationSize {
+    pub const ALL: &'static [AllocationSize] = &[
+        AllocationSize::Small,
+        AllocationSize::Mixed,
+        AllocationSize::Large,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AllocationSize::Small => "small (16-256 B)",
+            AllocationSize::Mixed => "mixed (16 B-64 KiB)",
+            AllocationSize::Large => "large (256 KiB-4 MiB)",
+        }
+    }
+
+    /// Drawn as a `u32`, like the corpus, so the plan is the same on every target.
+    fn sample(&self, rng: &mut ChaCha8Rng) -> usize {
+        let bytes: u32 = match self {
+            AllocationSize::Small => rng.gen_range(16..=256),
+            AllocationSize::Mixed => {
+                let power = 1 << rng.gen_range(4..16);
+                power + rng.gen_range(0..power)
+            }
+            AllocationSize::Large => rng.gen_range(256 * 1024..=4 * 1024 * 1024),
+        };
+        bytes as usize
+    }
+
+    fn live_allocations(&self) -> usize {
+        match self {
+            AllocationSize::Small | AllocationSize::Mixed => LIVE_ALLOCATIONS,
+            AllocationSize::Large => LARGE_LIVE_ALLOCATIONS,
+        }
+    }
+}
+
+/// Allocation and free pairs per second of one size class.
+#[derive(Debug, Serialize)]
+pub struct AllocationThroughput {
+    pub size: AllocationSize,
+    pub threads: usize,
+    pub operations: u64,
+    pub duration: Duration,
+    pub million_operations_per_second: f64,
+    pub score: f64,
+}
+
+/// Cost of the first write to freshly mapped memory.
+#[derive(Debug, Serialize)]
+pub struct PageFaultResult {
+    pub bytes: usize,
+    /// Average time of the first write to each 4 KiB page.
+    pub nanoseconds_per_fault: f64,
+    pub gigabytes_per_second: f64,
+    /// The same with `MADV_HUGEPAGE`, `None` when the kernel has no transparent huge
+    /// pages.
+    pub huge_pages: Option<HugePageResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HugePageResult {
+    pub gigabytes_per_second: f64,
+    /// Bytes of the region the kernel backed with huge pages, 0 when the
+    /// `transparent_hugepage` mode is `never` or no huge page was free.
+    pub huge_page_bytes: u64,
+    /// Throughput relative to the regular pages.
+    pub speedup: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AllocatorResult {
+    /// Global allocator of the build, see `GLOBAL_ALLOCATOR`.
+    pub allocator: &'static str,
+    pub duration: Duration,
+    pub threads: usize,
+    pub single_thread: Vec<AllocationThroughput>,
+    pub multi_thread: Vec<AllocationThroughput>,
+    /// Not scored, it measures the kernel rather than the allocator. `None` outside of
+    /// Linux.
+    pub page_faults: Option<PageFaultResult>,
+    /// Geometric mean of the allocation scores of both variants, `None` when a size
+    /// couldn't be measured.
+    pub score: Option<f64>,
+}
+
+/// `threads` threads, each with its own live allocations, share `operations`
+/// allocation and free pairs of `size`. The score compares them with the single core
+/// of the reference machine.
+fn churn(size: AllocationSize, threads: usize, operations: u64) -> AllocationThroughput {
+    let threads = threads.max(1);
+    let per_thread = operations / threads as u64;
+    let barrier = Barrier::new(threads + 1);
+
+    let duration = thread::scope(|scope| {
+        for thread in 0..threads {
+            let barrier = &barrier;
+            scope.spawn(move || {
+                let mut rng = corpus::rng(11 + thread as u64);
+                let live_allocations = size.live_allocations();
+                let plan: Vec<(usize, usize)> = (0..PLAN_LENGTH)
+                    .map(|_| {
+                        (
+                            corpus::index(&mut rng, live_allocations),
+                            size.sample(&mut rng),
+                        )
+                    })
+                    .collect();
+                let mut live: Vec<Vec<u8>> = vec![Vec::new(); live_allocations];
+                barrier.wait();
+                for (slot, bytes) in plan.iter().cycle().take(per_thread as usize) {
+                    let mut allocation = Vec::with_capacity(*bytes);
+                    allocation.push(1);
+                    // Frees the allocation that was in the slot
+                    live[*slot] = black_box(allocation);
+                }
+                barrier.wait();
+            });
+        }
+        barrier.wait();
+        let start = Instant::now();
+        barrier.wait();
+        start.elapsed()
+    });
+
+    let operations = per_thread * threads as u64;
+    let seconds = duration.as_secs_f64();
+    let million_operations_per_second = if seconds > 0.0 {
+        operations as f64 / 1e6 / seconds
+    } else {
+        0.0
+    };
+    AllocationThroughput {
+        size,
+        threads,
+        operations,
+        duration,
+        million_operations_per_second,
+        score: scoring::throughput_score(
+            million_operations_per_second,
+            scoring::reference_allocation_throughput(size),
+        ),
+    }
+}
+
+/// Bytes of the mapping that contains `address` backed by transparent huge pages,
+/// according to `/proc/self/smaps`.
+#[cfg(target_os = "linux")]
+fn anon_huge_page_bytes(address: usize) -> u64 {
+    let Ok(smaps) = std::fs::read_to_string("/proc/self/smaps") else {
+        return 0;
+    };
+    let mut inside = false;
+    for line in smaps.lines() {
+        // Each mapping starts with its address range, `7f12a000-7f12b000 rw-p ...`
+        let range = line.split_once(' ').and_then(|(range, _)| {
+            let (start, end) = range.split_once('-')?;
+            Some(usize::from_str_radix(start, 16).ok()?..usize::from_str_radix(end, 16).ok()?)
+        });
+        if let Some(range) = range {
+            inside = range.contains(&address);
+        } else if let Some(kilobytes) = line.strip_prefix("AnonHugePages:").filter(|_| inside) {
+            let kilobytes = kilobytes.trim().trim_end_matches("kB").trim();
+            return kilobytes.parse::<u64>().unwrap_or(0) * 1024;
+        }
+    }
+    0
+}
+
+/// Maps `PAGE_FAULT_BYTES` aligned to a huge page, advises the kernel to back them
+/// with huge pages or not and writes to each 4 KiB page for the first time. Returns the
+/// time of the writes and the bytes backed by huge pages, `None` if the mapping fails
+/// or, for huge pages, if the kernel does not support them.
+#[cfg(target_os = "linux")]
+fn touch_pages(huge: bool) -> Option<(Duration, u64)> {
+    let length = PAGE_FAULT_BYTES + HUGE_PAGE_SIZE;
+    // SAFETY: a fresh private anonymous mapping, only written inside its bounds and
+    // unmapped before returning.
+    unsafe {
+        let mapping = libc::mmap(
+            std::ptr::null_mut(),
+            length,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        if mapping == libc::MAP_FAILED {
+            return None;
+        }
+        let start = (mapping as usize).next_multiple_of(HUGE_PAGE_SIZE) as *mut u8;
+        let advice = if huge {
+            libc::MADV_HUGEPAGE
+        } else {
+            libc::MADV_NOHUGEPAGE
+        };
+        // Without transparent huge pages both fail, the regular pages are measured
+        // anyway
+        let advised = libc::madvise(start.cast(), PAGE_FAULT_BYTES, advice) == 0;
+        let result = (advised || !huge).then(|| {
+            let begin = Instant::now();
+            for offset in (0..PAGE_FAULT_BYTES).step_by(PAGE_SIZE) {
+                start.add(offset).write_volatile(1);
+            }
+            (begin.elapsed(), anon_huge_page_bytes(start as usize))
+        });
+        libc::munmap(mapping, length);
+        result
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn page_faults() -> Option<PageFaultResult> {
+    let gigabytes_per_second = |duration: Duration| {
+        let seconds = duration.as_secs_f64();
+        if seconds > 0.0 {
+            PAGE_FAULT_BYTES as f64 / 1e9 / seconds
+        } else {
+            0.0
+        }
+    };
+    // Alternated and the fastest run of each kept, so neither pays alone for memory the
+    // hypervisor or the kernel has yet to hand out
+    let mut regular: Option<Duration> = None;
+    let mut huge: Option<(Duration, u64)> = None;
+    for _ in 0..PAGE_FAULT_RUNS {
+        let (duration, _) = touch_pages(false)?;
+        regular = Some(regular.map_or(duration, |fastest| fastest.min(duration)));
+        if let Some((duration, huge_page_bytes)) = touch_pages(true) {
+            huge = match huge {
+                Some(fastest) if fastest.0 <= duration => Some(fastest),
+                _ => Some((duration, huge_page_bytes)),
+            };
+        }
+    }
+    let regular = regular?;
+    let faults = PAGE_FAULT_BYTES / PAGE_SIZE;
+    Some(PageFaultResult {
+        bytes: PAGE_FAULT_BYTES,
+        nanoseconds_per_fault: regular.as_secs_f64() * 1e9 / faults as f64,
+        gigabytes_per_second: gigabytes_per_second(regular),
+        huge_pages: huge.map(|(huge, huge_page_bytes)| HugePageResult {
+            gigabytes_per_second: gigabytes_per_second(huge),
+            huge_page_bytes,
+            speedup: regular.as_secs_f64() / huge.as_secs_f64().max(f64::MIN_POSITIVE),
+        }),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn page_faults() -> Option<PageFaultResult> {
+    None
+}
+
+/// Allocates and frees every size class for a fiftieth of `operations` (large sizes
+/// for a 2000th), first on one thread and then split over `num_threads`, and measures the
+/// page fault cost with and without transparent huge pages.
+pub fn benchmark_allocator(operations: u64, num_threads: usize) -> AllocatorResult {
+    let operations_of = |size: AllocationSize| match size {
+        AllocationSize::Large => operations / OPERATIONS_DIVISOR / LARGE_DIVISOR,
+        _ => operations / OPERATIONS_DIVISOR,
+    };
+
+    let start = Instant::now();
+    let single_thread: Vec<AllocationThroughput> = AllocationSize::ALL
+        .iter()
+        .map(|size| churn(*size, 1, operations_of(*size)))
+        .collect();
+    let multi_thread: Vec<AllocationThroughput> = AllocationSize::ALL
+        .iter()
+        .map(|size| churn(*size, num_threads, operations_of(*size)))
+        .collect();
+    let page_faults = page_faults();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = single_thread
+        .iter()
+        .chain(&multi_thread)
+        .map(|result| result.score)
+        .collect();
+    AllocatorResult {
+        allocator: GLOBAL_ALLOCATOR,
+        duration,
+        threads: num_threads.max(1),
+        single_thread,
+        multi_thread,
+        page_faults,
+        score: scoring::composite_score(&scores),
+    }
+}
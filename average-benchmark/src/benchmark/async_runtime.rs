@@ -0,0 +1,356 @@
+use serde::Serialize;
+use std::hint::black_box;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use crate::scoring;
+
+// Spawning and joining a task takes around half a microsecond, a message on a channel
+// around a hundred nanoseconds and an echo request several microseconds, each kernel
+// does this fraction of the profile's operations on every worker count.
+const SPAWN_DIVISOR: u64 = 200;
+const CHANNEL_DIVISOR: u64 = 40;
+const ECHO_DIVISOR: u64 = 2_000;
+
+// Tasks spawned before joining them, like a server fanning out a request.
+const SPAWN_BATCH: u64 = 1024;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+// Echo clients of each worker, so there is always another connection ready while one
+// waits for its answer.
+const CONNECTIONS_PER_WORKER: usize = 4;
+
+const MESSAGE_SIZE: usize = 64;
+
+/// Kernels of the async runtime workload, each one scored in operations per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AsyncKernel {
+    /// Spawning tasks in batches and awaiting their join handles.
+    Spawn,
+    /// Sending integers through bounded mpsc channels, one producer and one consumer
+    /// task per worker.
+    Channel,
+    /// Requests of 64 bytes echoed back by a server over loopback TCP connections.
+    TcpEcho,
+}
+
+impl AsyncKernel {
+    pub const ALL: &'static [AsyncKernel] = &[
+        AsyncKernel::Spawn,
+        AsyncKernel::Channel,
+        AsyncKernel::TcpEcho,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AsyncKernel::Spawn => "spawn and join",
+            AsyncKernel::Channel => "channel",
+            AsyncKernel::TcpEcho => "loopback TCP echo",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            AsyncKernel::Spawn => "tasks/s",
+            AsyncKernel::Channel => "messages/s",
+            AsyncKernel::TcpEcho => "requests/s",
+        }
+    }
+
+    fn divisor(&self) -> u64 {
+        match self {
+            AsyncKernel::Spawn => SPAWN_DIVISOR,
+            AsyncKernel::Channel => CHANNEL_DIVISOR,
+            AsyncKernel::TcpEcho => ECHO_DIVISOR,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AsyncKernelResult {
+    pub kernel: AsyncKernel,
+    pub operations: u64,
+    pub duration: Duration,
+    pub operations_per_second: f64,
+}
+
+/// A kernel that failed, e.g. the TCP echo when loopback connections are not allowed.
+#[derive(Debug, Serialize)]
+pub struct AsyncKernelError {
+    pub kernel: AsyncKernel,
+    pub error: String,
+}
+
+/// The kernels on a runtime with `workers` worker threads.
+#[derive(Debug, Serialize)]
+pub struct AsyncPoint {
+    pub workers: usize,
+    /// Without the kernels that failed, which are in `errors`.
+    pub kernels: Vec<AsyncKernelResult>,
+    pub errors: Vec<AsyncKernelError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AsyncRuntimeResult {
+    pub duration: Duration,
+    pub threads: usize,
+    /// With 1, 2, 4... and `threads` workers.
+    pub points: Vec<AsyncPoint>,
+    /// Geometric mean of the kernel scores with one worker and with `threads` workers,
+    /// both compared with the single core of the reference machine. `None` when a
+    /// kernel failed or couldn't be measured on either of them, a score without it
+    /// wouldn't be comparable.
+    pub score: Option<f64>,
+}
+
+/// Spawns `tasks` tasks from `workers` tasks and joins them.
+async fn spawn_join(tasks: u64, workers: usize) -> io::Result<()> {
+    let per_spawner = tasks / workers as u64;
+    let spawners: Vec<_> = (0..workers)
+        .map(|_| {
+            tokio::spawn(async move {
+                let mut sum = 0;
+                let mut handles = Vec::with_capacity(SPAWN_BATCH as usize);
+                let mut spawned = 0;
+                while spawned < per_spawner {
+                    let batch = SPAWN_BATCH.min(per_spawner - spawned);
+                    handles.extend(
+                        (spawned..spawned + batch)
+                            .map(|task| tokio::spawn(async move { black_box(task) })),
+                    );
+                    for handle in handles.drain(..) {
+                        sum += handle.await?;
+                    }
+                    spawned += batch;
+                }
+                io::Result::Ok(sum)
+            })
+        })
+        .collect();
+    for spawner in spawners {
+        if spawner.await?? != per_spawner * per_spawner.saturating_sub(1) / 2 {
+            return Err(io::Error::other("a task was lost"));
+        }
+    }
+    Ok(())
+}
+
+/// Sends `messages` messages over `workers` channels.
+async fn channel(messages: u64, workers: usize) -> io::Result<()> {
+    let per_channel = messages / workers as u64;
+    let mut consumers = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        let (sender, mut receiver) = mpsc::channel::<u64>(CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            for message in 0..per_channel {
+                // The consumer only stops early if it panicked, which its handle reports
+                if sender.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        consumers.push(tokio::spawn(async move {
+            let mut sum = 0;
+            while let Some(message) = receiver.recv().await {
+                sum += message;
+            }
+            sum
+        }));
+    }
+    for consumer in consumers {
+        if consumer.await? != per_channel * per_channel.saturating_sub(1) / 2 {
+            return Err(io::Error::other("a message was lost"));
+        }
+    }
+    Ok(())
+}
+
+async fn echo_server(listener: TcpListener) {
+    while let Ok((mut stream, _)) = listener.accept().await {
+        tokio::spawn(async move {
+            let mut message = [0; MESSAGE_SIZE];
+            while stream.read_exact(&mut message).await.is_ok() {
+                if stream.write_all(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Connects `workers * CONNECTIONS_PER_WORKER` clients to an echo server on the
+/// loopback interface and returns the time they take to send `requests` requests.
+async fn tcp_echo(requests: u64, workers: usize) -> io::Result<Duration> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    let server = tokio::spawn(echo_server(listener));
+
+    let connections = workers * CONNECTIONS_PER_WORKER;
+    let mut streams = Vec::with_capacity(connections);
+    for _ in 0..connections {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        streams.push(stream);
+    }
+
+    let per_connection = requests / connections as u64;
+    let start = Instant::now();
+    let clients: Vec<_> = streams
+        .into_iter()
+        .enumerate()
+        .map(|(client, mut stream)| {
+            tokio::spawn(async move {
+                let mut response = [0; MESSAGE_SIZE];
+                for request in 0..per_connection {
+                    let message = [(client as u64 ^ request) as u8; MESSAGE_SIZE];
+                    stream.write_all(&message).await?;
+                    stream.read_exact(&mut response).await?;
+                    if response != message {
+                        return Err(io::Error::other("the echo changed the message"));
+                    }
+                }
+                io::Result::Ok(())
+            })
+        })
+        .collect();
+    for client in clients {
+        client.await??;
+    }
+    let duration = start.elapsed();
+    server.abort();
+    Ok(duration)
+}
+
+/// Runs `kernel` for `operations` operations and returns the operations done, a
+/// multiple of the tasks sharing them, and their time.
+async fn measure(
+    kernel: AsyncKernel,
+    operations: u64,
+    workers: usize,
+) -> io::Result<(u64, Duration)> {
+    match kernel {
+        AsyncKernel::Spawn => {
+            let start = Instant::now();
+            spawn_join(operations, workers).await?;
+            Ok((
+                operations / workers as u64 * workers as u64,
+                start.elapsed(),
+            ))
+        }
+        AsyncKernel::Channel => {
+            let start = Instant::now();
+            channel(operations, workers).await?;
+            Ok((
+                operations / workers as u64 * workers as u64,
+                start.elapsed(),
+            ))
+        }
+        AsyncKernel::TcpEcho => {
+            let connections = (workers * CONNECTIONS_PER_WORKER) as u64;
+            let operations = operations.max(connections);
+            let duration = tcp_echo(operations, workers).await?;
+            Ok((operations / connections * connections, duration))
+        }
+    }
+}
+
+/// Runs `kernel` for its fraction of `operations` on a runtime with `workers` worker
+/// threads.
+fn run_kernel(
+    kernel: AsyncKernel,
+    operations: u64,
+    workers: usize,
+) -> io::Result<AsyncKernelResult> {
+    let operations = (operations / kernel.divisor()).max(workers as u64);
+    // Built, run and dropped on a thread of its own, the caller may already be inside a
+    // runtime, like the command line client
+    let (operations, duration) = thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_multi_thread()
+                    .worker_threads(workers)
+                    .enable_all()
+                    .build()?
+                    .block_on(measure(kernel, operations, workers))
+            })
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("the kernel panicked")))
+    })?;
+
+    let seconds = duration.as_secs_f64();
+    Ok(AsyncKernelResult {
+        kernel,
+        operations,
+        duration,
+        operations_per_second: if seconds > 0.0 {
+            operations as f64 / seconds
+        } else {
+            0.0
+        },
+    })
+}
+
+/// Runs every kernel of the async runtime workload, each for its fraction of
+/// `operations`, on tokio runtimes with 1, 2, 4... and `num_threads` workers.
+pub fn benchmark_async_runtime(operations: u64, num_threads: usize) -> AsyncRuntimeResult {
+    let start = Instant::now();
+    let points: Vec<AsyncPoint> = super::scaling_thread_counts(num_threads)
+        .into_iter()
+        .map(|workers| {
+            let mut point = AsyncPoint {
+                workers,
+                kernels: Vec::new(),
+                errors: Vec::new(),
+            };
+            for kernel in AsyncKernel::ALL {
+                match run_kernel(*kernel, operations, workers) {
+                    Ok(result) => point.kernels.push(result),
+                    Err(e) => point.errors.push(AsyncKernelError {
+                        kernel: *kernel,
+                        error: e.to_string(),
+                    }),
+                }
+            }
+            point
+        })
+        .collect();
+    let duration = start.elapsed();
+
+    let single_worker = points.first().into_iter().flat_map(|point| {
+        point.kernels.iter().map(|result| {
+            scoring::throughput_score(
+                result.operations_per_second,
+                scoring::reference_async_throughput(result.kernel),
+            )
+        })
+    });
+    let all_workers = points.last().into_iter().flat_map(|point| {
+        point.kernels.iter().map(|result| {
+            scoring::throughput_score(
+                result.operations_per_second,
+                scoring::reference_async_throughput(result.kernel),
+            )
+        })
+    });
+    let scores: Vec<f64> = single_worker.chain(all_workers).collect();
+    let complete = [points.first(), points.last()]
+        .into_iter()
+        .flatten()
+        .all(|point| point.errors.is_empty());
+    AsyncRuntimeResult {
+        duration,
+        threads: num_threads.max(1),
+        score: if complete {
+            scoring::composite_score(&scores)
+        } else {
+            None
+        },
+        points,
+    }
+}
@@ -0,0 +1,232 @@
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use super::corpus::{self, CorpusKind};
+use crate::scoring;
+
+// The corpus is compressed in independent blocks, like the chunks of a log pipeline,
+// which is also what lets the multi-thread variant spread them over the threads.
+const BLOCK_SIZE: usize = 1024 * 1024;
+
+// Compressing is one or two orders of magnitude slower than hashing, so each codec
+// processes this fraction of the profile's bytes to keep the workload about as long as
+// the others.
+const BYTES_DIVISOR: usize = 16;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Codecs of the compression workload, at the default level of each one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompressionCodec {
+    Lz4,
+    Zstd,
+    Deflate,
+}
+
+impl CompressionCodec {
+    pub const ALL: &'static [CompressionCodec] = &[
+        CompressionCodec::Lz4,
+        CompressionCodec::Zstd,
+        CompressionCodec::Deflate,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompressionCodec::Lz4 => "LZ4",
+            CompressionCodec::Zstd => "zstd",
+            CompressionCodec::Deflate => "deflate",
+        }
+    }
+
+    fn compress(&self, block: &[u8]) -> Vec<u8> {
+        match self {
+            CompressionCodec::Lz4 => lz4_flex::compress_prepend_size(block),
+            CompressionCodec::Zstd => {
+                zstd::bulk::compress(block, ZSTD_LEVEL).expect("zstd compresses in memory")
+            }
+            CompressionCodec::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(block)
+                    .expect("deflate compresses in memory");
+                encoder.finish().expect("deflate compresses in memory")
+            }
+        }
+    }
+
+    /// `None` when the codec rejects the data it compressed itself.
+    fn decompress(&self, compressed: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            CompressionCodec::Lz4 => lz4_flex::decompress_size_prepended(compressed).ok(),
+            CompressionCodec::Zstd => zstd::bulk::decompress(compressed, BLOCK_SIZE).ok(),
+            CompressionCodec::Deflate => {
+                let mut block = Vec::with_capacity(BLOCK_SIZE);
+                DeflateDecoder::new(compressed)
+                    .read_to_end(&mut block)
+                    .ok()?;
+                Some(block)
+            }
+        }
+    }
+}
+
+/// Throughput of one codec, in MB/s of uncompressed data.
+#[derive(Debug, Serialize)]
+pub struct CompressionThroughput {
+    pub codec: CompressionCodec,
+    pub threads: usize,
+    pub compress_megabytes_per_second: f64,
+    pub decompress_megabytes_per_second: f64,
+    /// Uncompressed size divided by the compressed size.
+    pub ratio: f64,
+    /// Whether decompressing gave back the original blocks. The scores of an invalid
+    /// codec are left out of the workload score.
+    pub valid: bool,
+    pub compress_score: f64,
+    pub decompress_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompressionResult {
+    pub duration: Duration,
+    /// Uncompressed bytes processed by each codec in each direction.
+    pub bytes: usize,
+    pub threads: usize,
+    pub single_thread: Vec<CompressionThroughput>,
+    pub multi_thread: Vec<CompressionThroughput>,
+    /// Geometric mean of the compression and decompression scores of every valid codec,
+    /// `None` when no codec is valid.
+    pub score: Option<f64>,
+}
+
+/// Blocks of the corpus adding up to about `bytes`, a third of each kind.
+fn corpus_blocks(bytes: usize) -> Vec<Vec<u8>> {
+    let per_kind = (bytes / CorpusKind::ALL.len()).max(BLOCK_SIZE);
+    CorpusKind::ALL
+        .iter()
+        .flat_map(|kind| {
+            corpus::generate(*kind, per_kind)
+                .chunks(BLOCK_SIZE)
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn measure(
+    codec: CompressionCodec,
+    blocks: &[Vec<u8>],
+    bytes: usize,
+    pool: Option<&rayon::ThreadPool>,
+) -> CompressionThroughput {
+    let compress = |block: &Vec<u8>| codec.compress(block);
+    let start = Instant::now();
+    let compressed: Vec<Vec<u8>> = match pool {
+        Some(pool) => pool.install(|| blocks.par_iter().map(compress).collect()),
+        None => blocks.iter().map(compress).collect(),
+    };
+    let compress_duration = start.elapsed();
+
+    let decompress = |block: &Vec<u8>| codec.decompress(block);
+    let start = Instant::now();
+    let decompressed: Vec<Option<Vec<u8>>> = match pool {
+        Some(pool) => pool.install(|| compressed.par_iter().map(decompress).collect()),
+        None => compressed.iter().map(decompress).collect(),
+    };
+    let decompress_duration = start.elapsed();
+    let valid = decompressed
+        .iter()
+        .zip(blocks)
+        .all(|(decompressed, block)| decompressed.as_ref() == Some(block));
+
+    let threads = pool.map_or(1, rayon::ThreadPool::current_num_threads);
+    let compressed_bytes: usize = compressed.iter().map(Vec::len).sum();
+    let compress_megabytes_per_second = scoring::megabytes_per_second(bytes, compress_duration);
+    let decompress_megabytes_per_second = scoring::megabytes_per_second(bytes, decompress_duration);
+    let (compress_reference, decompress_reference) =
+        scoring::reference_compression_throughput(codec);
+    CompressionThroughput {
+        codec,
+        threads,
+        compress_megabytes_per_second,
+        decompress_megabytes_per_second,
+        ratio: bytes as f64 / compressed_bytes.max(1) as f64,
+        valid,
+        compress_score: scoring::throughput_score(
+            compress_megabytes_per_second,
+            compress_reference,
+        ),
+        decompress_score: scoring::throughput_score(
+            decompress_megabytes_per_second,
+            decompress_reference,
+        ),
+    }
+}
+
+/// Compresses and decompresses the corpus with every codec, first on one thread and
+/// then on `num_threads`. Each codec processes a sixteenth of `bytes` in each direction.
+pub fn benchmark_compression(bytes: usize, num_threads: usize) -> CompressionResult {
+    let blocks = corpus_blocks(bytes / BYTES_DIVISOR);
+    let bytes = blocks.iter().map(Vec::len).sum();
+    let pool = super::thread_pool(num_threads);
+
+    let start = Instant::now();
+    let single_thread: Vec<CompressionThroughput> = CompressionCodec::ALL
+        .iter()
+        .map(|codec| measure(*codec, &blocks, bytes, None))
+        .collect();
+    let multi_thread: Vec<CompressionThroughput> = CompressionCodec::ALL
+        .iter()
+        .map(|codec| measure(*codec, &blocks, bytes, Some(&pool)))
+        .collect();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = single_thread
+        .iter()
+        .chain(&multi_thread)
+        .filter(|result| result.valid)
+        .flat_map(|result| [result.compress_score, result.decompress_score])
+        .collect();
+    CompressionResult {
+        duration,
+        bytes,
+        threads: pool.current_num_threads(),
+        single_thread,
+        multi_thread,
+        score: scoring::composite_score(&scores),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_codec_round_trips_the_corpus() {
+        let blocks: Vec<Vec<u8>> = CorpusKind::ALL
+            .iter()
+            .map(|kind| corpus::generate(*kind, 64 * 1024))
+            .collect();
+        let bytes = blocks.iter().map(Vec::len).sum();
+        for codec in CompressionCodec::ALL {
+            let throughput = measure(*codec, &blocks, bytes, None);
+            assert!(throughput.valid, "{} round trip", codec.name());
+            assert!(throughput.ratio > 1.0, "{} ratio", codec.name());
+        }
+    }
+
+    #[test]
+    fn corrupted_data_is_rejected() {
+        let block = corpus::text(64 * 1024);
+        for codec in CompressionCodec::ALL {
+            let mut compressed = codec.compress(&block);
+            compressed.truncate(compressed.len() / 2);
+            assert_ne!(codec.decompress(&compressed), Some(block.clone()));
+        }
+    }
+}
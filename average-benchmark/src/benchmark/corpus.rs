@@ -0,0 +1,312 @@
+//! Deterministic inputs for the workloads that process data. The generator is seeded and
+//! portable, so every machine processes exactly the same bytes.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+const WORDS: &[&str] = &[
+    "the",
+    "of",
+    "and",
+    "to",
+    "in",
+    "is",
+    "that",
+    "for",
+    "it",
+    "as",
+    "was",
+    "with",
+    "be",
+    "by",
+    "on",
+    "not",
+    "he",
+    "this",
+    "are",
+    "or",
+    "his",
+    "from",
+    "at",
+    "which",
+    "but",
+    "have",
+    "an",
+    "had",
+    "they",
+    "you",
+    "were",
+    "their",
+    "one",
+    "all",
+    "we",
+    "can",
+    "her",
+    "has",
+    "there",
+    "been",
+    "if",
+    "more",
+    "when",
+    "will",
+    "would",
+    "who",
+    "so",
+    "no",
+    "processor",
+    "benchmark",
+    "memory",
+    "cache",
+    "thread",
+    "latency",
+    "throughput",
+    "compiler",
+    "kernel",
+    "scheduler",
+    "request",
+    "response",
+    "server",
+    "client",
+    "network",
+    "storage",
+    "señal",
+    "número",
+    "canción",
+    "año",
+    "niño",
+    "über",
+    "naïve",
+    "café",
+    "日本",
+    "данные",
+];
+
+const EVENTS: &[&str] = &[
+    "login", "logout", "purchase", "view", "search", "error", "upload",
+];
+
+const LEVELS: &[&str] = &["DEBUG", "INFO", "INFO", "INFO", "WARN", "ERROR"];
+const SERVICES: &[&str] = &["gateway", "auth", "orders", "billing", "search"];
+const METHODS: &[&str] = &["GET", "GET", "GET", "POST", "PUT", "DELETE"];
+const RESOURCES: &[&str] = &["users", "orders", "products", "invoices", "sessions"];
+const STATUSES: &[u16] = &[200, 200, 200, 201, 204, 301, 400, 401, 404, 500, 503];
+
+/// Seeded generator shared by the workloads, so their inputs are the same everywhere.
+pub fn rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Random index below `len`. Drawn as a `u32`, ranges of `usize` draw a different
+/// number of bits on 32 and 64-bit targets, which would change the inputs.
+pub fn index(rng: &mut ChaCha8Rng, len: usize) -> usize {
+    rng.gen_range(0..len as u32) as usize
+}
+
+/// Kinds of data in the corpus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CorpusKind {
+    /// Prose with some non-ASCII words.
+    Text,
+    /// Newline delimited JSON records like the ones of a log pipeline.
+    Json,
+    /// Records of counters, identifiers and floats with some noise.
+    Binary,
+}
+
+impl CorpusKind {
+    pub const ALL: &'static [CorpusKind] =
+        &[CorpusKind::Text, CorpusKind::Json, CorpusKind::Binary];
+}
+
+/// About `size` bytes of prose, in lines of up to 80 bytes.
+pub fn text(size: usize) -> Vec<u8> {
+    let mut rng = rng(1);
+    let mut text = String::with_capacity(size + 128);
+    let mut line_length = 0;
+    while text.len() < size {
+        let word = WORDS[index(&mut rng, WORDS.len())];
+        if line_length + word.len() >= 80 {
+            text.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            text.push(' ');
+            line_length += 1;
+        }
+        text.push_str(word);
+        line_length += word.len();
+        if rng.gen_ratio(1, 12) {
+            text.push('.');
+            line_length += 1;
+        }
+    }
+    text.into_bytes()
+}
+
+/// About `size` bytes of newline delimited JSON records.
+pub fn json(size: usize) -> Vec<u8> {
+    let mut rng = rng(2);
+    let mut json = String::with_capacity(size + 256);
+    let mut id: u64 = 0;
+    while json.len() < size {
+        id += 1;
+        let tags: Vec<String> = (0..rng.gen_range(0..4))
+            .map(|_| format!("\"{}\"", WORDS[index(&mut rng, WORDS.len())]))
+            .collect();
+        json.push_str(&format!(
+            "{{\"id\":{},\"timestamp\":{},\"user\":\"user{}\",\"event\":\"{}\",\"latency_ms\":{:.3},\"success\":{},\"tags\":[{}]}}\n",
+            id,
+            1_700_000_000_000u64 + id * 37,
+            rng.gen_range(0..5000),
+            EVENTS[index(&mut rng, EVENTS.len())],
+            rng.gen_range(0.1..250.0),
+            rng.gen_ratio(9, 10),
+            tags.join(",")
+        ));
+    }
+    json.into_bytes()
+}
+
+/// `size` bytes of fixed-size little-endian records: a slowly increasing timestamp, an
+/// identifier from a small set, a float measurement and a random checksum.
+pub fn binary(size: usize) -> Vec<u8> {
+    let mut rng = rng(3);
+    let mut binary = Vec::with_capacity(size + 24);
+    let mut timestamp: u64 = 1_700_000_000_000;
+    while binary.len() < size {
+        timestamp += rng.gen_range(1..1000);
+        binary.extend_from_slice(&timestamp.to_le_bytes());
+        binary.extend_from_slice(&rng.gen_range(0u32..64).to_le_bytes());
+        binary.extend_from_slice(&rng.gen_range(0.0f32..100.0).to_le_bytes());
+        binary.extend_from_slice(&rng.gen::<u64>().to_le_bytes());
+    }
+    binary.truncate(size);
+    binary
+}
+
+/// About `size` bytes of CSV rows after a header. A tenth of the comments are quoted
+/// because they contain commas, and some of those also quotes.
+pub fn csv(size: usize) -> Vec<u8> {
+    let mut rng = rng(9);
+    let mut csv = String::with_capacity(size + 256);
+    csv.push_str("id,timestamp,user,event,latency_ms,success,comment\n");
+    let mut id: u64 = 0;
+    while csv.len() < size {
+        id += 1;
+        let words: Vec<&str> = (0..rng.gen_range(1..8))
+            .map(|_| WORDS[index(&mut rng, WORDS.len())])
+            .collect();
+        let comment = if rng.gen_ratio(1, 10) {
+            let quoted = if rng.gen_ratio(1, 3) { "\"\"" } else { "" };
+            format!("\"{}{}, {}\"", quoted, words.join(" "), id)
+        } else {
+            words.join(" ")
+        };
+        csv.push_str(&format!(
+            "{},{},user{},{},{:.3},{},{}\n",
+            id,
+            1_700_000_000_000u64 + id * 37,
+            rng.gen_range(0..5000),
+            EVENTS[index(&mut rng, EVENTS.len())],
+            rng.gen_range(0.1..250.0),
+            rng.gen_ratio(9, 10),
+            comment
+        ));
+    }
+    csv.into_bytes()
+}
+
+/// About `size` bytes of access log lines of a web service, with timestamps, levels,
+/// requests, client addresses, statuses, latencies and user emails.
+pub fn logs(size: usize) -> Vec<u8> {
+    let mut rng = rng(10);
+    let mut logs = String::with_capacity(size + 256);
+    let mut milliseconds: u64 = 0;
+    while logs.len() < size {
+        milliseconds += rng.gen_range(1..50);
+        let seconds = milliseconds / 1000;
+        logs.push_str(&format!(
+            "2023-11-14T{:02}:{:02}:{:02}.{:03}Z {} [{}] {} /api/{}/{} from 10.{}.{}.{} status={} latency={}ms user={}{}@example.com\n",
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+            milliseconds % 1000,
+            LEVELS[index(&mut rng, LEVELS.len())],
+            SERVICES[index(&mut rng, SERVICES.len())],
+            METHODS[index(&mut rng, METHODS.len())],
+            RESOURCES[index(&mut rng, RESOURCES.len())],
+            rng.gen_range(1..100_000),
+            rng.gen_range(0..256),
+            rng.gen_range(0..256),
+            rng.gen_range(1..255),
+            STATUSES[index(&mut rng, STATUSES.len())],
+            // Mostly fast, with a long tail of slow requests
+            rng.gen_range(1..20u32).pow(rng.gen_range(1..4)),
+            WORDS[index(&mut rng, 48)],
+            rng.gen_range(0..1000),
+        ));
+    }
+    logs.into_bytes()
+}
+
+/// About `size` bytes of the given kind.
+pub fn generate(kind: CorpusKind, size: usize) -> Vec<u8> {
+    match kind {
+        CorpusKind::Text => text(size),
+        CorpusKind::Json => json(size),
+        CorpusKind::Binary => binary(size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(bytes: &[u8]) -> String {
+        blake3::hash(bytes).to_hex().to_string()
+    }
+
+    #[test]
+    fn generator_is_pinned() {
+        let mut rng = rng(1);
+        assert_eq!(rng.gen::<u64>(), 7424550030962593201);
+        assert_eq!(index(&mut rng, 1000), 984);
+    }
+
+    // A change here changes what every machine processes, and so the scores
+    #[test]
+    fn corpus_is_pinned() {
+        assert_eq!(&text(64)[..16], b"would we to thre");
+        assert_eq!(
+            digest(&text(4096)),
+            "5c1ce1241f956c8e8300527092682a0ebec8afea8e28526b8baaf18e97834d4a"
+        );
+        assert_eq!(
+            digest(&json(4096)),
+            "11bcaad9317153a7840537c03acc7be1b14a9e727161dea8a090d226f5c7a2dc"
+        );
+        assert_eq!(
+            digest(&binary(4096)),
+            "464d021b4a16302de77f6b730a9af487a2beb946041e8e000db04105e4627547"
+        );
+        assert_eq!(
+            digest(&csv(4096)),
+            "83aa63b407209c4641049dda9de1af715954a2fab1ebad3d7df975b5c4cf24fa"
+        );
+        assert_eq!(
+            digest(&logs(4096)),
+            "2c8f3503eb097f9a100fe70c7623a70840866d423b10009cd4527946793cf002"
+        );
+    }
+
+    #[test]
+    fn sizes_are_as_documented() {
+        assert_eq!(binary(1000).len(), 1000);
+        for kind in CorpusKind::ALL {
+            let data = generate(*kind, 1000);
+            assert!(data.len() >= 1000 && data.len() < 1300);
+        }
+        assert!(std::str::from_utf8(&text(4096)).is_ok());
+    }
+}
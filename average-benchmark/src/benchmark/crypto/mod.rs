@@ -0,0 +1,295 @@
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::digest;
+use serde::Serialize;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::scoring;
+
+mod software;
+
+// Data is processed in records of the maximum TLS plaintext size, like a TLS stream.
+const RECORD_SIZE: usize = 16 * 1024;
+
+// The software implementations process this fraction of the bytes, they can be orders of
+// magnitude slower.
+const SOFTWARE_SHARE: usize = 16;
+
+// Speedup over the software implementation from which the accelerated path counts as
+// taken.
+const ACCELERATED_SPEEDUP: f64 = 2.0;
+
+/// Algorithms of the crypto workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CryptoAlgorithm {
+    Sha256,
+    Blake3,
+    Aes256Gcm,
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+}
+
+impl CryptoAlgorithm {
+    pub const ALL: &'static [CryptoAlgorithm] = &[
+        CryptoAlgorithm::Sha256,
+        CryptoAlgorithm::Blake3,
+        CryptoAlgorithm::Aes256Gcm,
+        CryptoAlgorithm::ChaCha20Poly1305,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CryptoAlgorithm::Sha256 => "SHA-256",
+            CryptoAlgorithm::Blake3 => "BLAKE3",
+            CryptoAlgorithm::Aes256Gcm => "AES-256-GCM",
+            CryptoAlgorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        }
+    }
+
+    /// CPU extensions detected on this machine for which the implementation has an
+    /// accelerated path, `None` when it has none for them. SHA-256, AES-GCM and
+    /// ChaCha20-Poly1305 come from ring and BLAKE3 from the blake3 crate, both select
+    /// their path at runtime from the same detection. Whether it was taken is observed
+    /// against the software implementation, see [`CryptoThroughput::accelerated`].
+    #[cfg(target_arch = "x86_64")]
+    pub fn detected_cpu_features(&self) -> Option<&'static str> {
+        use std::arch::is_x86_feature_detected as detected;
+        match self {
+            CryptoAlgorithm::Sha256 if detected!("sha") && detected!("ssse3") => Some("SHA-NI"),
+            CryptoAlgorithm::Sha256 if detected!("avx2") && detected!("bmi2") => Some("AVX2"),
+            CryptoAlgorithm::Sha256 if detected!("avx") => Some("AVX"),
+            CryptoAlgorithm::Sha256 if detected!("ssse3") => Some("SSSE3"),
+            CryptoAlgorithm::Blake3 if detected!("avx512f") && detected!("avx512vl") => {
+                Some("AVX-512")
+            }
+            CryptoAlgorithm::Blake3 if detected!("avx2") => Some("AVX2"),
+            CryptoAlgorithm::Blake3 if detected!("sse4.1") => Some("SSE4.1"),
+            CryptoAlgorithm::Blake3 if detected!("sse2") => Some("SSE2"),
+            CryptoAlgorithm::Aes256Gcm
+                if detected!("vaes") && detected!("vpclmulqdq") && detected!("avx2") =>
+            {
+                Some("VAES+VPCLMULQDQ")
+            }
+            CryptoAlgorithm::Aes256Gcm if detected!("aes") && detected!("pclmulqdq") => {
+                Some("AES-NI+PCLMULQDQ")
+            }
+            CryptoAlgorithm::ChaCha20Poly1305 if detected!("avx2") => Some("AVX2"),
+            CryptoAlgorithm::ChaCha20Poly1305 if detected!("ssse3") => Some("SSSE3"),
+            _ => None,
+        }
+    }
+
+    /// As on x86_64. The blake3 crate builds its NEON path on every little-endian
+    /// aarch64 target.
+    #[cfg(target_arch = "aarch64")]
+    pub fn detected_cpu_features(&self) -> Option<&'static str> {
+        use std::arch::is_aarch64_feature_detected as detected;
+        match self {
+            CryptoAlgorithm::Sha256 if detected!("sha2") => Some("ARMv8 SHA2"),
+            CryptoAlgorithm::Aes256Gcm if detected!("aes") => Some("ARMv8 AES+PMULL"),
+            CryptoAlgorithm::Blake3 if cfg!(target_endian = "little") && detected!("neon") => {
+                Some("NEON")
+            }
+            CryptoAlgorithm::ChaCha20Poly1305 if detected!("neon") => Some("NEON"),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    pub fn detected_cpu_features(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Throughput of one algorithm of the crypto workload.
+#[derive(Debug, Serialize)]
+pub struct CryptoThroughput {
+    pub algorithm: CryptoAlgorithm,
+    /// CPU extensions detected for the algorithm's accelerated path, `None` when there
+    /// are none. Detected rather than measured, see
+    /// [`CryptoAlgorithm::detected_cpu_features`].
+    pub cpu_features: Option<&'static str>,
+    pub bytes: usize,
+    pub duration: Duration,
+    pub megabytes_per_second: f64,
+    /// Throughput of the software implementation in a sixteenth of the bytes, `None` for
+    /// ChaCha20-Poly1305, which has none, see [`process_software`].
+    pub software_megabytes_per_second: Option<f64>,
+    /// Whether the accelerated path was taken, meaning it was at least twice as fast as
+    /// the software implementation. `None` without one.
+    pub accelerated: Option<bool>,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CryptoResult {
+    pub duration: Duration,
+    pub algorithms: Vec<CryptoThroughput>,
+    /// Geometric mean of the algorithm scores, `None` when an algorithm couldn't be
+    /// measured.
+    pub score: Option<f64>,
+}
+
+const KEY: [u8; 32] = [0x42; 32];
+
+/// Nonce of the record `sequence`, unique per record like the TLS record sequence number.
+fn record_nonce(sequence: u64) -> [u8; aead::NONCE_LEN] {
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    nonce[4..].copy_from_slice(&sequence.to_be_bytes());
+    nonce
+}
+
+/// Hashes or encrypts `bytes` bytes, one TLS-sized record at a time.
+fn process(algorithm: CryptoAlgorithm, record: &mut [u8; RECORD_SIZE], bytes: usize) {
+    let records = bytes.div_ceil(RECORD_SIZE);
+    let seal = |aead: &'static aead::Algorithm, record: &mut [u8]| {
+        let key = LessSafeKey::new(UnboundKey::new(aead, &KEY).expect("valid key"));
+        for sequence in 0..records as u64 {
+            let tag = key
+                .seal_in_place_separate_tag(
+                    Nonce::assume_unique_for_key(record_nonce(sequence)),
+                    Aad::empty(),
+                    record,
+                )
+                .expect("record within the AEAD limits");
+            black_box(tag.as_ref());
+        }
+    };
+
+    match algorithm {
+        CryptoAlgorithm::Sha256 => {
+            for _ in 0..records {
+                black_box(digest::digest(&digest::SHA256, black_box(&*record)));
+            }
+        }
+        CryptoAlgorithm::Blake3 => {
+            for _ in 0..records {
+                black_box(blake3::hash(black_box(&*record)));
+            }
+        }
+        CryptoAlgorithm::Aes256Gcm => seal(&aead::AES_256_GCM, record),
+        CryptoAlgorithm::ChaCha20Poly1305 => seal(&aead::CHACHA20_POLY1305, record),
+    }
+}
+
+/// Like [`process`] with the software implementation, returns how long it took.
+/// ChaCha20-Poly1305 has none, it was designed to be fast without dedicated instructions
+/// and ring's path for it is plain SIMD.
+fn process_software(
+    algorithm: CryptoAlgorithm,
+    record: &mut [u8; RECORD_SIZE],
+    bytes: usize,
+) -> Option<Duration> {
+    let records = bytes.div_ceil(RECORD_SIZE);
+    let start = Instant::now();
+    match algorithm {
+        CryptoAlgorithm::Sha256 => {
+            for _ in 0..records {
+                black_box(software::sha256(black_box(&record[..])));
+            }
+        }
+        CryptoAlgorithm::Blake3 => {
+            for _ in 0..records {
+                black_box(software::blake3(black_box(record)));
+            }
+        }
+        CryptoAlgorithm::Aes256Gcm => {
+            let cipher = software::Aes256Gcm::new(&KEY);
+            for sequence in 0..records as u64 {
+                black_box(cipher.seal_in_place(&record_nonce(sequence), record));
+            }
+        }
+        CryptoAlgorithm::ChaCha20Poly1305 => return None,
+    }
+    Some(start.elapsed())
+}
+
+/// Hashes and encrypts `bytes` bytes with every algorithm of the workload.
+pub fn benchmark_crypto(bytes: usize) -> CryptoResult {
+    let mut record: Box<[u8; RECORD_SIZE]> = (0..RECORD_SIZE)
+        .map(|i| (i % 251) as u8)
+        .collect::<Box<[u8]>>()
+        .try_into()
+        .expect("record-sized");
+    let bytes = bytes.div_ceil(RECORD_SIZE) * RECORD_SIZE;
+    let software_bytes = (bytes / SOFTWARE_SHARE).div_ceil(RECORD_SIZE) * RECORD_SIZE;
+
+    let start = Instant::now();
+    let algorithms: Vec<CryptoThroughput> = CryptoAlgorithm::ALL
+        .iter()
+        .map(|algorithm| {
+            let algorithm_start = Instant::now();
+            process(*algorithm, &mut record, bytes);
+            let duration = algorithm_start.elapsed();
+
+            let megabytes_per_second = scoring::megabytes_per_second(bytes, duration);
+            let software_megabytes_per_second =
+                process_software(*algorithm, &mut record, software_bytes)
+                    .map(|duration| scoring::megabytes_per_second(software_bytes, duration));
+            CryptoThroughput {
+                algorithm: *algorithm,
+                cpu_features: algorithm.detected_cpu_features(),
+                bytes,
+                duration,
+                megabytes_per_second,
+                software_megabytes_per_second,
+                accelerated: software_megabytes_per_second
+                    .map(|software| megabytes_per_second >= ACCELERATED_SPEEDUP * software),
+                score: scoring::throughput_score(
+                    megabytes_per_second,
+                    scoring::reference_crypto_throughput(*algorithm),
+                ),
+            }
+        })
+        .collect();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = algorithms.iter().map(|result| result.score).collect();
+    CryptoResult {
+        duration,
+        algorithms,
+        score: scoring::composite_score(&scores),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn algorithms_serialize_with_stable_names() {
+        let names: Vec<String> = CryptoAlgorithm::ALL
+            .iter()
+            .map(|algorithm| serde_json::to_string(algorithm).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                r#""sha256""#,
+                r#""blake3""#,
+                r#""aes256-gcm""#,
+                r#""chacha20-poly1305""#
+            ]
+        );
+    }
+
+    #[test]
+    fn measures_every_algorithm_in_one_record() {
+        let result = benchmark_crypto(1);
+        assert_eq!(result.algorithms.len(), CryptoAlgorithm::ALL.len());
+        for throughput in &result.algorithms {
+            assert_eq!(throughput.bytes, RECORD_SIZE);
+            assert!(throughput.megabytes_per_second > 0.0);
+            assert_eq!(
+                throughput.software_megabytes_per_second.is_some(),
+                throughput.algorithm != CryptoAlgorithm::ChaCha20Poly1305
+            );
+            assert_eq!(
+                throughput.accelerated.is_some(),
+                throughput.software_megabytes_per_second.is_some()
+            );
+        }
+        assert!(result.score.is_some());
+    }
+}
@@ -0,0 +1,284 @@
+//! Portable implementations of the crypto workload algorithms, its baseline for how much
+//! the accelerated paths gain. They only process the benchmark's own data, the AES here
+//! is table-based and not constant-time.
+
+use blake3::platform::{self, Platform};
+use blake3::{IncrementCounter, BLOCK_LEN, CHUNK_LEN, OUT_LEN};
+use sha2::{Digest, Sha256};
+
+use super::RECORD_SIZE;
+
+/// SHA-256 of the sha2 crate, built with its `force-soft` feature.
+pub(super) fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+// BLAKE3 initialization vector and domain flags, as in the specification
+const BLAKE3_IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+const CHUNK_START: u8 = 1 << 0;
+const CHUNK_END: u8 = 1 << 1;
+const PARENT: u8 = 1 << 2;
+const ROOT: u8 = 1 << 3;
+
+const RECORD_CHUNKS: usize = RECORD_SIZE / CHUNK_LEN;
+
+/// BLAKE3 of a record through the blake3 crate's portable platform, which the crate
+/// exposes for benchmarks. A record is a power of two of chunks, so its tree is a
+/// balanced binary tree of them.
+pub(super) fn blake3(record: &[u8; RECORD_SIZE]) -> [u8; 32] {
+    const { assert!(RECORD_CHUNKS.is_power_of_two()) };
+
+    let portable = Platform::portable();
+    let chunks: Vec<&[u8; CHUNK_LEN]> = record
+        .chunks_exact(CHUNK_LEN)
+        .map(|chunk| chunk.try_into().expect("chunk-sized"))
+        .collect();
+    let mut nodes = [0u8; RECORD_CHUNKS * OUT_LEN];
+    portable.hash_many(
+        &chunks,
+        &BLAKE3_IV,
+        0,
+        IncrementCounter::Yes,
+        0,
+        CHUNK_START,
+        CHUNK_END,
+        &mut nodes,
+    );
+
+    let mut len = RECORD_CHUNKS;
+    while len > 1 {
+        let flags = if len == 2 { PARENT | ROOT } else { PARENT };
+        for parent in 0..len / 2 {
+            let block: [u8; BLOCK_LEN] = nodes[parent * BLOCK_LEN..][..BLOCK_LEN]
+                .try_into()
+                .expect("two chaining values");
+            let mut cv = BLAKE3_IV;
+            portable.compress_in_place(&mut cv, &block, block.len() as u8, 0, flags);
+            nodes[parent * OUT_LEN..][..OUT_LEN]
+                .copy_from_slice(&platform::le_bytes_from_words_32(&cv));
+        }
+        len /= 2;
+    }
+    nodes[..OUT_LEN].try_into().expect("root hash")
+}
+
+// AES S-box, computed as the multiplicative inverse in GF(2^8) followed by the affine
+// transformation of the specification.
+const SBOX: [u8; 256] = {
+    let mut sbox = [0u8; 256];
+    let mut x = 0;
+    while x < 256 {
+        // x^254 is the inverse of x, and 0 for 0
+        let (mut inverse, mut power, mut exponent) = (1u8, x as u8, 254);
+        while exponent != 0 {
+            if exponent & 1 == 1 {
+                inverse = gf_mul(inverse, power);
+            }
+            power = gf_mul(power, power);
+            exponent >>= 1;
+        }
+        sbox[x] = inverse
+            ^ inverse.rotate_left(1)
+            ^ inverse.rotate_left(2)
+            ^ inverse.rotate_left(3)
+            ^ inverse.rotate_left(4)
+            ^ 0x63;
+        x += 1;
+    }
+    sbox
+};
+
+const fn xtime(b: u8) -> u8 {
+    (b << 1) ^ ((b >> 7) * 0x1B)
+}
+
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+const AES256_ROUNDS: usize = 14;
+
+/// AES-256 encryption on bytes, the state in column-major order.
+struct Aes256 {
+    round_keys: [[u8; 16]; AES256_ROUNDS + 1],
+}
+
+impl Aes256 {
+    fn new(key: &[u8; 32]) -> Self {
+        let mut words = [[0u8; 4]; 4 * (AES256_ROUNDS + 1)];
+        for (word, bytes) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(bytes);
+        }
+        let mut rcon = 1u8;
+        for i in 8..words.len() {
+            let mut word = words[i - 1];
+            if i % 8 == 0 {
+                word.rotate_left(1);
+                word = word.map(|byte| SBOX[byte as usize]);
+                word[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if i % 8 == 4 {
+                word = word.map(|byte| SBOX[byte as usize]);
+            }
+            for (byte, previous) in word.iter_mut().zip(words[i - 8]) {
+                *byte ^= previous;
+            }
+            words[i] = word;
+        }
+
+        let mut round_keys = [[0u8; 16]; AES256_ROUNDS + 1];
+        for (round_key, round_words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            round_key.copy_from_slice(round_words.as_flattened());
+        }
+        Aes256 { round_keys }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        xor_block(block, &self.round_keys[0]);
+        for round in 1..=AES256_ROUNDS {
+            // SubBytes and ShiftRows, row r rotates left by r columns
+            let state = *block;
+            for (i, byte) in block.iter_mut().enumerate() {
+                let (row, column) = (i % 4, i / 4);
+                *byte = SBOX[state[row + 4 * ((column + row) % 4)] as usize];
+            }
+            if round < AES256_ROUNDS {
+                for column in block.chunks_exact_mut(4) {
+                    let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+                    let all = a0 ^ a1 ^ a2 ^ a3;
+                    column[0] ^= all ^ xtime(a0 ^ a1);
+                    column[1] ^= all ^ xtime(a1 ^ a2);
+                    column[2] ^= all ^ xtime(a2 ^ a3);
+                    column[3] ^= all ^ xtime(a3 ^ a0);
+                }
+            }
+            xor_block(block, &self.round_keys[round]);
+        }
+    }
+}
+
+fn xor_block(block: &mut [u8], other: &[u8]) {
+    for (byte, other) in block.iter_mut().zip(other) {
+        *byte ^= other;
+    }
+}
+
+/// Multiplication in GHASH's GF(2^128), bit by bit as in NIST SP 800-38D.
+fn ghash_mul(x: u128, h: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    let mut product = 0;
+    let mut v = h;
+    for bit in (0..128).rev() {
+        if (x >> bit) & 1 == 1 {
+            product ^= v;
+        }
+        v = if v & 1 == 1 { (v >> 1) ^ R } else { v >> 1 };
+    }
+    product
+}
+
+/// AES-256-GCM with a 96-bit nonce and no associated data.
+pub(super) struct Aes256Gcm {
+    cipher: Aes256,
+    h: u128,
+}
+
+impl Aes256Gcm {
+    pub(super) fn new(key: &[u8; 32]) -> Self {
+        let cipher = Aes256::new(key);
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+        Aes256Gcm {
+            cipher,
+            h: u128::from_be_bytes(h),
+        }
+    }
+
+    /// Encrypts `data` in place and returns the tag.
+    pub(super) fn seal_in_place(&self, nonce: &[u8; 12], data: &mut [u8]) -> [u8; 16] {
+        let mut counter = [0u8; 16];
+        counter[..12].copy_from_slice(nonce);
+        counter[15] = 1;
+        let mut tag_mask = counter;
+        self.cipher.encrypt_block(&mut tag_mask);
+
+        let mut hash = 0u128;
+        for (block, number) in data.chunks_mut(16).zip(2u32..) {
+            counter[12..].copy_from_slice(&number.to_be_bytes());
+            let mut keystream = counter;
+            self.cipher.encrypt_block(&mut keystream);
+            xor_block(block, &keystream);
+
+            let mut padded = [0u8; 16];
+            padded[..block.len()].copy_from_slice(block);
+            hash = ghash_mul(hash ^ u128::from_be_bytes(padded), self.h);
+        }
+        let lengths = (data.len() as u128) * 8;
+        hash = ghash_mul(hash ^ lengths, self.h);
+
+        let mut tag = hash.to_be_bytes();
+        xor_block(&mut tag, &tag_mask);
+        tag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey};
+    use ring::digest;
+
+    fn record() -> Box<[u8; RECORD_SIZE]> {
+        let record: Vec<u8> = (0..RECORD_SIZE).map(|i| (i % 251) as u8).collect();
+        record.try_into().unwrap()
+    }
+
+    #[test]
+    fn sbox_matches_the_specification() {
+        assert_eq!(&SBOX[..4], &[0x63, 0x7C, 0x77, 0x7B]);
+        assert_eq!(SBOX[0x53], 0xED);
+        assert_eq!(SBOX[0xFF], 0x16);
+    }
+
+    #[test]
+    fn hashes_match_the_accelerated_ones() {
+        let record = record();
+        assert_eq!(
+            sha256(&record[..]),
+            digest::digest(&digest::SHA256, &record[..]).as_ref()
+        );
+        assert_eq!(&blake3(&record), blake3::hash(&record[..]).as_bytes());
+    }
+
+    #[test]
+    fn aes_gcm_matches_ring() {
+        let key = [0x42; 32];
+        let nonce = [7u8; 12];
+        // Not a whole number of blocks, to cover the padding
+        let mut expected = record()[..1000].to_vec();
+        let mut data = expected.clone();
+
+        let ring_key = LessSafeKey::new(UnboundKey::new(&aead::AES_256_GCM, &key).unwrap());
+        let ring_tag = ring_key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut expected,
+            )
+            .unwrap();
+        let tag = Aes256Gcm::new(&key).seal_in_place(&nonce, &mut data);
+
+        assert_eq!(data, expected);
+        assert_eq!(&tag[..], ring_tag.as_ref());
+    }
+}
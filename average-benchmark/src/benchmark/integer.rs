@@ -0,0 +1,404 @@
+use rand::Rng;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasherDefault;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use super::corpus;
+use crate::scoring;
+
+// Keys of the map kernels, half of the lookups miss. Large enough to spill out of the
+// L2 cache like the maps of a real service.
+const MAP_KEYS: usize = 1 << 20;
+
+// Elements of each array sorted by the sort kernels.
+const SORT_LENGTH: usize = 1 << 20;
+
+// Map and sort operations are tens of instructions, those kernels do this fraction of
+// the operations of the interpreter.
+const OPERATIONS_DIVISOR: u64 = 20;
+
+/// Kernels of the integer workload, each one scored on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntegerKernel {
+    /// Bytecode interpreter running Collatz sequences, in instructions.
+    Interpreter,
+    /// Insert, lookup and remove mix on a `HashMap`, in operations.
+    HashMap,
+    /// Insert, lookup, range and remove mix on a `BTreeMap`, in operations.
+    #[serde(rename = "btree-map")]
+    BTreeMap,
+    /// Sorting random integers, in elements.
+    SortRandom,
+    /// Sorting sorted integers after swapping 1% of them with random ones, in elements.
+    SortNearlySorted,
+}
+
+impl IntegerKernel {
+    pub const ALL: &'static [IntegerKernel] = &[
+        IntegerKernel::Interpreter,
+        IntegerKernel::HashMap,
+        IntegerKernel::BTreeMap,
+        IntegerKernel::SortRandom,
+        IntegerKernel::SortNearlySorted,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntegerKernel::Interpreter => "interpreter",
+            IntegerKernel::HashMap => "HashMap",
+            IntegerKernel::BTreeMap => "BTreeMap",
+            IntegerKernel::SortRandom => "sort (random)",
+            IntegerKernel::SortNearlySorted => "sort (nearly sorted)",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegerKernelResult {
+    pub kernel: IntegerKernel,
+    pub operations: u64,
+    pub duration: Duration,
+    pub million_operations_per_second: f64,
+    /// Value computed by the kernel, the same on every machine for the same profile.
+    pub checksum: u64,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegerResult {
+    pub duration: Duration,
+    pub kernels: Vec<IntegerKernelResult>,
+    /// Geometric mean of the kernel scores, `None` when a kernel couldn't be measured.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Instruction {
+    Load(usize, u64),
+    Add(usize, usize, usize),
+    Mul(usize, usize, usize),
+    And(usize, usize, usize),
+    ShiftRight(usize, usize, u32),
+    JumpIfZero(usize, usize),
+    JumpIfNotEqual(usize, usize, usize),
+    Jump(usize),
+}
+
+/// Counts the Collatz steps of 1, 2, 3... The branch on the parity of each value is as
+/// hard to predict as the branches of real interpreted code.
+fn collatz_program() -> Vec<Instruction> {
+    use Instruction::*;
+    // r0: value, r1: start, r2: steps, r3: parity, r4: 1, r5: 3, r6: 0
+    vec![
+        Load(1, 1),
+        Load(4, 1),
+        Load(5, 3),
+        Load(0, 1),
+        // 4: loop
+        And(3, 0, 4),
+        JumpIfZero(3, 9),
+        Mul(0, 0, 5),
+        Add(0, 0, 4),
+        Jump(10),
+        // 9: even
+        ShiftRight(0, 0, 1),
+        // 10: check
+        Add(2, 2, 4),
+        JumpIfNotEqual(0, 4, 4),
+        Add(1, 1, 4),
+        Add(0, 1, 6),
+        Jump(4),
+    ]
+}
+
+/// Runs `program` until it has executed at least `budget` instructions and returns the
+/// number executed with the final registers.
+fn interpret(program: &[Instruction], budget: u64) -> (u64, [u64; 8]) {
+    let mut registers = [0u64; 8];
+    let mut pc = 0;
+    let mut executed = 0;
+    while executed < budget {
+        executed += 1;
+        match program[pc] {
+            Instruction::Load(target, value) => registers[target] = value,
+            Instruction::Add(target, a, b) => {
+                registers[target] = registers[a].wrapping_add(registers[b])
+            }
+            Instruction::Mul(target, a, b) => {
+                registers[target] = registers[a].wrapping_mul(registers[b])
+            }
+            Instruction::And(target, a, b) => registers[target] = registers[a] & registers[b],
+            Instruction::ShiftRight(target, a, bits) => registers[target] = registers[a] >> bits,
+            Instruction::JumpIfZero(register, target) => {
+                if registers[register] == 0 {
+                    pc = target;
+                    continue;
+                }
+            }
+            Instruction::JumpIfNotEqual(a, b, target) => {
+                if registers[a] != registers[b] {
+                    pc = target;
+                    continue;
+                }
+            }
+            Instruction::Jump(target) => {
+                pc = target;
+                continue;
+            }
+        }
+        pc += 1;
+    }
+    (executed, registers)
+}
+
+/// Keys and operations of the map kernels. There is one operation less than keys, so
+/// every pass over the keys pairs each key with a different operation.
+struct MapInput {
+    keys: Vec<u64>,
+    operations: Vec<u8>,
+}
+
+impl MapInput {
+    fn generate() -> Self {
+        let mut rng = corpus::rng(4);
+        MapInput {
+            keys: (0..MAP_KEYS)
+                .map(|_| rng.gen_range(0..2 * MAP_KEYS as u64))
+                .collect(),
+            operations: (0..MAP_KEYS - 1).map(|_| rng.gen_range(0..6)).collect(),
+        }
+    }
+
+    fn iter(&self, operations: u64) -> impl Iterator<Item = (usize, (&u64, &u8))> {
+        self.keys
+            .iter()
+            .cycle()
+            .zip(self.operations.iter().cycle())
+            .take(operations as usize)
+            .enumerate()
+    }
+}
+
+/// Half lookups, a third inserts and the rest removals, in random order.
+fn run_hash_map(input: &MapInput, operations: u64) -> u64 {
+    let mut map: HashMap<u64, u64, BuildHasherDefault<DefaultHasher>> = HashMap::default();
+    let mut checksum = 0u64;
+    for (i, (key, operation)) in input.iter(operations) {
+        match operation {
+            0..=2 => checksum = checksum.wrapping_add(*map.get(key).unwrap_or(&0)),
+            3 | 4 => {
+                map.insert(*key, i as u64);
+            }
+            _ => {
+                map.remove(key);
+            }
+        }
+    }
+    checksum.wrapping_add(map.len() as u64)
+}
+
+/// Like `run_hash_map`, but a third of the lookups find the next key in order instead.
+fn run_btree_map(input: &MapInput, operations: u64) -> u64 {
+    let mut map: BTreeMap<u64, u64> = BTreeMap::new();
+    let mut checksum = 0u64;
+    for (i, (key, operation)) in input.iter(operations) {
+        match operation {
+            0 | 1 => checksum = checksum.wrapping_add(*map.get(key).unwrap_or(&0)),
+            2 => {
+                if let Some((next, _)) = map.range(key..).next() {
+                    checksum = checksum.wrapping_add(*next);
+                }
+            }
+            3 | 4 => {
+                map.insert(*key, i as u64);
+            }
+            _ => {
+                map.remove(key);
+            }
+        }
+    }
+    checksum.wrapping_add(map.len() as u64)
+}
+
+fn sort_input(nearly_sorted: bool) -> Vec<u64> {
+    let mut rng = corpus::rng(5);
+    if !nearly_sorted {
+        return (0..SORT_LENGTH).map(|_| rng.gen()).collect();
+    }
+    let mut values: Vec<u64> = (0..SORT_LENGTH as u64).map(|value| value * 16).collect();
+    for _ in 0..SORT_LENGTH / 100 {
+        let (a, b) = (
+            corpus::index(&mut rng, SORT_LENGTH),
+            corpus::index(&mut rng, SORT_LENGTH),
+        );
+        values.swap(a, b);
+    }
+    values
+}
+
+/// Sorts copies of `input` until `operations` elements were sorted. The copies are made
+/// outside of the measured time.
+fn run_sort(input: &[u64], operations: u64) -> (u64, Duration, u64) {
+    let rounds = operations.div_ceil(input.len() as u64).max(1);
+    let mut duration = Duration::ZERO;
+    let mut checksum = 0u64;
+    for _ in 0..rounds {
+        let mut values = input.to_vec();
+        let start = Instant::now();
+        values.sort();
+        duration += start.elapsed();
+        checksum = checksum.wrapping_add(black_box(&values)[values.len() / 2]);
+    }
+    (rounds * input.len() as u64, duration, checksum)
+}
+
+fn run_kernel(kernel: IntegerKernel, operations: u64) -> IntegerKernelResult {
+    let (operations, duration, checksum) = match kernel {
+        IntegerKernel::Interpreter => {
+            let program = collatz_program();
+            let start = Instant::now();
+            let (executed, registers) = interpret(black_box(&program), operations);
+            (executed, start.elapsed(), registers[2])
+        }
+        IntegerKernel::HashMap | IntegerKernel::BTreeMap => {
+            let operations = match kernel {
+                // B-tree operations chase several pointers, five times fewer take as long
+                IntegerKernel::BTreeMap => operations / OPERATIONS_DIVISOR / 5,
+                _ => operations / OPERATIONS_DIVISOR,
+            };
+            let input = MapInput::generate();
+            let start = Instant::now();
+            let checksum = match kernel {
+                IntegerKernel::HashMap => run_hash_map(black_box(&input), operations),
+                _ => run_btree_map(black_box(&input), operations),
+            };
+            (operations, start.elapsed(), checksum)
+        }
+        IntegerKernel::SortRandom | IntegerKernel::SortNearlySorted => {
+            let input = sort_input(kernel == IntegerKernel::SortNearlySorted);
+            run_sort(&input, operations / OPERATIONS_DIVISOR)
+        }
+    };
+
+    let seconds = duration.as_secs_f64();
+    let million_operations_per_second = if seconds > 0.0 {
+        operations as f64 / 1e6 / seconds
+    } else {
+        0.0
+    };
+    IntegerKernelResult {
+        kernel,
+        operations,
+        duration,
+        million_operations_per_second,
+        checksum,
+        score: scoring::throughput_score(
+            million_operations_per_second,
+            scoring::reference_integer_throughput(kernel),
+        ),
+    }
+}
+
+/// Runs every kernel of the integer workload on its seeded input. `operations` sets the
+/// instructions of the interpreter, the hash map and sort
+/// kernels do a twentieth of them and the B-tree a hundredth.
+pub fn benchmark_integer(operations: u64) -> IntegerResult {
+    let start = Instant::now();
+    let kernels: Vec<IntegerKernelResult> = IntegerKernel::ALL
+        .iter()
+        .map(|kernel| run_kernel(*kernel, operations))
+        .collect();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = kernels.iter().map(|result| result.score).collect();
+    IntegerResult {
+        duration,
+        kernels,
+        score: scoring::composite_score(&scores),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels_serialize_with_stable_names() {
+        let names: Vec<String> = IntegerKernel::ALL
+            .iter()
+            .map(|kernel| serde_json::to_string(kernel).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                r#""interpreter""#,
+                r#""hash-map""#,
+                r#""btree-map""#,
+                r#""sort-random""#,
+                r#""sort-nearly-sorted""#
+            ]
+        );
+    }
+
+    /// Steps from `start` to 1, at least one: the program checks for 1 after a step.
+    fn collatz_steps(start: u64) -> u64 {
+        let (mut value, mut steps) = (start, 0);
+        loop {
+            value = if value % 2 == 1 {
+                3 * value + 1
+            } else {
+                value / 2
+            };
+            steps += 1;
+            if value == 1 {
+                return steps;
+            }
+        }
+    }
+
+    #[test]
+    fn interpreter_counts_collatz_steps() {
+        let (executed, registers) = interpret(&collatz_program(), 10_000);
+        assert_eq!(executed, 10_000);
+        let (value, start, steps) = (registers[0], registers[1], registers[2]);
+        assert_eq!((value, start, steps), (34, 66, 1740));
+
+        // The starts below the current one are done, the current one is partway
+        let done: u64 = (1..start).map(collatz_steps).sum();
+        let mut expected = start;
+        for _ in done..steps {
+            expected = if expected % 2 == 1 {
+                3 * expected + 1
+            } else {
+                expected / 2
+            };
+        }
+        assert_eq!(value, expected);
+    }
+
+    // The checksums are the same on every machine, a change here is a change of the
+    // kernels and so of the scores
+    #[test]
+    fn checksums_are_pinned() {
+        let result = benchmark_integer(100_000);
+        let checksums: Vec<(IntegerKernel, u64, u64)> = result
+            .kernels
+            .iter()
+            .map(|kernel| (kernel.kernel, kernel.operations, kernel.checksum))
+            .collect();
+        assert_eq!(
+            checksums,
+            [
+                (IntegerKernel::Interpreter, 100_000, 17481),
+                (IntegerKernel::HashMap, 5000, 1629),
+                (IntegerKernel::BTreeMap, 1000, 183706505),
+                (IntegerKernel::SortRandom, 1 << 20, 9228370692686717684),
+                (IntegerKernel::SortNearlySorted, 1 << 20, 8388608),
+            ]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use core_affinity::CoreId;
+use serde::Serialize;
+use std::hint::spin_loop;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Each pair is measured in this many batches and the fastest one is kept, so a batch
+// interrupted by the scheduler does not count.
+const BATCHES: u32 = 3;
+
+/// A value alone in its cache line (two lines, for the adjacent line prefetcher), so
+/// the handoff measures the coherence traffic of that line only.
+#[repr(align(128))]
+struct CacheLine(AtomicU64);
+
+/// Shared state the contention test hammers from every thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentionPrimitive {
+    /// `fetch_add` on a single atomic counter.
+    AtomicCounter,
+    /// Incrementing a counter behind a `std::sync::Mutex`.
+    Mutex,
+}
+
+impl ContentionPrimitive {
+    pub const ALL: &'static [ContentionPrimitive] = &[
+        ContentionPrimitive::AtomicCounter,
+        ContentionPrimitive::Mutex,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContentionPrimitive::AtomicCounter => "atomic counter",
+            ContentionPrimitive::Mutex => "mutex",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentionResult {
+    pub primitive: ContentionPrimitive,
+    pub threads: usize,
+    pub operations: u64,
+    pub duration: Duration,
+    pub million_operations_per_second: f64,
+    /// Whether the counter ended up with every increment.
+    pub valid: bool,
+}
+
+/// Core-to-core latencies and contended throughput. Not scored: the latencies describe
+/// the topology of the machine (CCXs, chiplets and sockets) rather than its speed.
+#[derive(Debug, Serialize)]
+pub struct LatencyResult {
+    /// CPUs of the matrix, in order.
+    pub cpus: Vec<usize>,
+    /// Whether the threads could be pinned to the CPUs. Without pinning the latencies
+    /// include migrations and are only indicative.
+    pub pinned: bool,
+    pub round_trips: u32,
+    /// One-way latency in nanoseconds from `cpus[i]` to `cpus[j]`, `None` on the
+    /// diagonal.
+    pub matrix_ns: Vec<Vec<Option<f64>>>,
+    pub min_ns: Option<f64>,
+    pub median_ns: Option<f64>,
+    pub max_ns: Option<f64>,
+    pub contention: Vec<ContentionResult>,
+}
+
+/// Bounces a cache line between `first` and `second` `round_trips` times and returns
+/// the one-way latency, whether both threads were pinned.
+fn ping_pong(first: CoreId, second: CoreId, round_trips: u32) -> (Duration, bool) {
+    let line = CacheLine(AtomicU64::new(0));
+    let barrier = Barrier::new(2);
+    thread::scope(|scope| {
+        let pong = scope.spawn(|| {
+            let pinned = core_affinity::set_for_current(second);
+            barrier.wait();
+            for round_trip in 0..round_trips as u64 {
+                while line.0.load(Ordering::Acquire) != 2 * round_trip + 1 {
+                    spin_loop();
+                }
+                line.0.store(2 * round_trip + 2, Ordering::Release);
+            }
+            pinned
+        });
+        let ping = scope.spawn(|| {
+            let pinned = core_affinity::set_for_current(first);
+            barrier.wait();
+            let start = Instant::now();
+            for round_trip in 0..round_trips as u64 {
+                line.0.store(2 * round_trip + 1, Ordering::Release);
+                while line.0.load(Ordering::Acquire) != 2 * round_trip + 2 {
+                    spin_loop();
+                }
+            }
+            (start.elapsed() / (2 * round_trips.max(1)), pinned)
+        });
+        let (latency, ping_pinned) = ping.join().expect("ping thread panicked");
+        let pong_pinned = pong.join().expect("pong thread panicked");
+        (latency, ping_pinned && pong_pinned)
+    })
+}
+
+/// `threads` threads, pinned round robin to `cores`, share `operations` increments of
+/// one counter.
+fn contend(
+    primitive: ContentionPrimitive,
+    cores: &[CoreId],
+    threads: usize,
+    operations: u64,
+) -> ContentionResult {
+    let threads = threads.max(1);
+    let per_thread = operations / threads as u64;
+    let counter = CacheLine(AtomicU64::new(0));
+    let mutex = Mutex::new(0u64);
+    let barrier = Barrier::new(threads + 1);
+
+    let duration = thread::scope(|scope| {
+        for thread in 0..threads {
+            let (counter, mutex, barrier) = (&counter, &mutex, &barrier);
+            let core = cores.get(thread % cores.len().max(1)).copied();
+            scope.spawn(move || {
+                if let Some(core) = core {
+                    core_affinity::set_for_current(core);
+                }
+                barrier.wait();
+                for _ in 0..per_thread {
+                    match primitive {
+                        ContentionPrimitive::AtomicCounter => {
+                            counter.0.fetch_add(1, Ordering::Relaxed);
+                        }
+                        ContentionPrimitive::Mutex => {
+                            *mutex.lock().expect("no thread panics holding the lock") += 1;
+                        }
+                    }
+                }
+                barrier.wait();
+            });
+        }
+        barrier.wait();
+        let start = Instant::now();
+        barrier.wait();
+        start.elapsed()
+    });
+
+    let operations = per_thread * threads as u64;
+    let counted = counter.0.into_inner() + mutex.into_inner().unwrap_or_default();
+
+    let seconds = duration.as_secs_f64();
+    ContentionResult {
+        primitive,
+        threads,
+        operations,
+        duration,
+        million_operations_per_second: if seconds > 0.0 {
+            operations as f64 / 1e6 / seconds
+        } else {
+            0.0
+        },
+        valid: counted == operations,
+    }
+}
+
+/// Measures the latency between every pair of CPUs the process may run on, then the
+/// contended throughput of `threads` threads sharing `operations` increments.
+pub fn benchmark_latency(round_trips: u32, threads: usize, operations: u64) -> LatencyResult {
+    let cores = core_affinity::get_core_ids().unwrap_or_default();
+    let batch = (round_trips / BATCHES).max(1);
+
+    // With fewer than two CPUs there is no pair to pin
+    let mut pinned = cores.len() > 1;
+    let mut matrix_ns = vec![vec![None; cores.len()]; cores.len()];
+    for i in 0..cores.len() {
+        for j in i + 1..cores.len() {
+            let fastest = (0..BATCHES)
+                .map(|_| {
+                    let (latency, pair_pinned) = ping_pong(cores[i], cores[j], batch);
+                    pinned &= pair_pinned;
+                    latency
+                })
+                .min()
+                .unwrap_or_default();
+            // A tenth of a nanosecond is below the noise and keeps the payload small
+            let nanoseconds = (fastest.as_secs_f64() * 1e10).round() / 10.0;
+            matrix_ns[i][j] = Some(nanoseconds);
+            matrix_ns[j][i] = Some(nanoseconds);
+        }
+    }
+
+    let mut latencies: Vec<f64> = matrix_ns.iter().flatten().flatten().copied().collect();
+    latencies.sort_by(f64::total_cmp);
+
+    let contention = ContentionPrimitive::ALL
+        .iter()
+        .map(|primitive| contend(*primitive, &cores, threads, operations))
+        .collect();
+
+    LatencyResult {
+        cpus: cores.iter().map(|core| core.id).collect(),
+        pinned,
+        round_trips: batch * BATCHES,
+        min_ns: latencies.first().copied(),
+        median_ns: latencies.get(latencies.len() / 2).copied(),
+        max_ns: latencies.last().copied(),
+        matrix_ns,
+        contention,
+    }
+}
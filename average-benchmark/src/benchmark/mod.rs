@@ -0,0 +1,229 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::scoring;
+
+pub mod allocator;
+pub mod async_runtime;
+pub mod compression;
+pub mod corpus;
+pub mod crypto;
+pub mod integer;
+pub mod latency;
+pub mod numeric;
+pub mod os_overhead;
+pub mod storage;
+pub mod text;
+
+/// Benchmarks that can be selected in the config file or with `--workloads`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Workload {
+    SingleThread,
+    MultiThread,
+    /// Hashing and authenticated encryption throughput.
+    Crypto,
+    /// LZ4, zstd and deflate over text, JSON and binary data.
+    Compression,
+    /// Bytecode interpreter, hash map, B-tree and sorting on seeded inputs.
+    Integer,
+    /// Core-to-core latency matrix and contended atomics and mutexes, not scored.
+    Latency,
+    /// Double precision n-body, FFT, sparse matrix-vector and stencil kernels.
+    Numeric,
+    /// JSON parsing, UTF-8 validation and transcoding, CSV tokenizing and regex searches.
+    Text,
+    /// Allocation and free throughput of small, mixed and large sizes, page faults and
+    /// transparent huge pages.
+    Allocator,
+    /// Task spawning, channel messages and loopback TCP echo requests on tokio runtimes
+    /// with 1, 2, 4... workers.
+    AsyncRuntime,
+    /// Null syscall, pipe context switch, fork and exec and futex wake latencies, not
+    /// scored.
+    OsOverhead,
+    /// Sequential and random I/O and fsync latency of a scratch file, not scored.
+    // Not part of any profile, see `Workload::OPT_IN`
+    Storage,
+}
+
+impl Workload {
+    /// Workloads of the profiles.
+    pub const ALL: &'static [Workload] = &[
+        Workload::SingleThread,
+        Workload::MultiThread,
+        Workload::Crypto,
+        Workload::Compression,
+        Workload::Integer,
+        Workload::Latency,
+        Workload::Numeric,
+        Workload::Text,
+        Workload::Allocator,
+        Workload::AsyncRuntime,
+        Workload::OsOverhead,
+    ];
+
+    /// Workloads no profile runs, they only run when selected.
+    pub const OPT_IN: &'static [Workload] = &[Workload::Storage];
+}
+
+#[derive(Debug, Serialize)]
+pub struct SingleThreadResult {
+    pub duration: Duration,
+    pub result: usize,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MultiThreadResult {
+    pub iterations: usize,
+    pub duration: Duration,
+    pub result: usize,
+    pub score: f64,
+    pub threads: usize,
+    pub min_time: Duration,
+    pub min_thread: usize,
+    pub max_time: Duration,
+    pub max_thread: usize,
+}
+
+/// Multi-thread score with a given number of threads, for the thread scaling runs.
+#[derive(Debug, Serialize)]
+pub struct ScalingPoint {
+    pub threads: usize,
+    pub score: f64,
+}
+
+#[inline]
+/// Performs a * b + c operation (i * i + i)
+/// with wrapping and black_box to accurately benchmark cpu performance.
+pub fn compute(sum: &mut usize, value: usize) {
+    *sum = sum.wrapping_add(black_box(value.wrapping_mul(value).wrapping_add(value)));
+}
+
+/// Runs `benchmark` `repetitions` times and returns the run with the median duration.
+/// `on_run` is called after every run with its repetition number, starting at 1.
+pub fn median_run<R>(
+    repetitions: u32,
+    mut benchmark: impl FnMut() -> R,
+    duration: impl Fn(&R) -> Duration,
+    mut on_run: impl FnMut(u32, &R),
+) -> R {
+    let mut runs: Vec<R> = (1..=repetitions.max(1))
+        .map(|repetition| {
+            let run = benchmark();
+            on_run(repetition, &run);
+            run
+        })
+        .collect();
+    runs.sort_by_key(|run| duration(run));
+    runs.swap_remove(runs.len() / 2)
+}
+
+/// Thread counts of the scaling runs: powers of two below `max_threads`, then
+/// `max_threads` itself.
+pub fn scaling_thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> =
+        std::iter::successors(Some(1usize), |threads| threads.checked_mul(2))
+            .take_while(|threads| *threads < max_threads)
+            .collect();
+    counts.push(max_threads.max(1));
+    counts
+}
+
+/// Rayon pool of `num_threads` threads for the workloads that run on several threads.
+pub(crate) fn thread_pool(num_threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .unwrap()
+}
+
+pub fn benchmark_single_thread(iterations: usize) -> SingleThreadResult {
+    let start = Instant::now();
+
+    let mut result = 0;
+    for i in 0..iterations {
+        compute(&mut result, i);
+    }
+
+    let duration = start.elapsed();
+
+    let score: f64 = scoring::workload_score(Workload::SingleThread, iterations, duration);
+
+    SingleThreadResult {
+        duration,
+        result,
+        score,
+    }
+}
+
+pub fn benchmark_multi_thread(num_threads: usize, iterations: usize) -> MultiThreadResult {
+    let pool = thread_pool(num_threads);
+
+    let mut total_sum = 0usize;
+    let mut min_time = Duration::MAX;
+    let mut max_time = Duration::ZERO;
+    let mut min_thread = 0;
+    let mut max_thread = 0;
+    let mut thread_id = 0;
+
+    let duration = pool.install(|| {
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let start: Instant = Instant::now();
+
+        (0..iterations)
+            .into_par_iter()
+            .fold(
+                || (0usize, Instant::now()),
+                |mut x, i| {
+                    compute(&mut x.0, i);
+                    x
+                },
+            )
+            .for_each(|x| {
+                let _ = tx.send(x);
+            });
+
+        let duration = start.elapsed();
+
+        while let Ok((i, instant)) = rx.try_recv() {
+            total_sum = total_sum.wrapping_add(i);
+
+            let elapsed = instant.elapsed();
+
+            if elapsed < min_time {
+                min_time = duration;
+                min_thread = thread_id;
+            }
+
+            if elapsed > max_time {
+                max_time = duration;
+                max_thread = thread_id;
+            }
+
+            thread_id += 1;
+        }
+
+        duration
+    });
+
+    let score: f64 = scoring::workload_score(Workload::MultiThread, iterations, duration);
+
+    MultiThreadResult {
+        iterations,
+        duration,
+        result: total_sum,
+        score,
+        threads: thread_id,
+        min_time,
+        min_thread,
+        max_time,
+        max_thread,
+    }
+}
@@ -0,0 +1,650 @@
+use rand::Rng;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::f64::consts::PI;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use super::corpus;
+use crate::scoring;
+
+// Bodies of the n-body kernel, every step computes all their pairwise interactions.
+const BODIES: usize = 2048;
+const SOFTENING: f64 = 0.01;
+const TIME_STEP: f64 = 1e-3;
+// Softened gravity between two bodies, counted the usual way: three subtractions, six
+// operations for the distance, two for the square root and division, three for the
+// cube, one for the mass and six for the accumulation.
+const FLOPS_PER_INTERACTION: u64 = 20;
+
+// Points of each transform of the FFT kernel, 1 MiB of complex values.
+const FFT_LENGTH: usize = 1 << 16;
+
+// Rows of the sparse matrix, each one with `ROW_ENTRIES` entries near the diagonal.
+// The matrix is about 50 MB, far larger than the caches like the matrices of a solver.
+const SPARSE_ROWS: usize = 1 << 18;
+const ROW_ENTRIES: usize = 16;
+const SPARSE_BAND: usize = 4096;
+// Rows of each task of the product, a single row is too little work to split.
+const ROWS_PER_TASK: usize = 1024;
+
+// Side of the Jacobi grid, boundary included. Two grids of 32 MiB.
+const GRID_SIZE: usize = 2048;
+
+/// Kernels of the numeric workload, in double precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NumericKernel {
+    /// All-pairs gravitational n-body steps, in GFLOP/s.
+    NBody,
+    /// Complex radix-2 FFT and its inverse, in GFLOP/s.
+    Fft,
+    /// Sparse matrix-vector product with a CSR matrix, in GB/s.
+    #[serde(rename = "spmv")]
+    SpMV,
+    /// Jacobi sweeps of a five-point stencil over a 2D grid, in GB/s.
+    Stencil,
+}
+
+impl NumericKernel {
+    pub const ALL: &'static [NumericKernel] = &[
+        NumericKernel::NBody,
+        NumericKernel::Fft,
+        NumericKernel::SpMV,
+        NumericKernel::Stencil,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumericKernel::NBody => "n-body",
+            NumericKernel::Fft => "FFT",
+            NumericKernel::SpMV => "SpMV (CSR)",
+            NumericKernel::Stencil => "Jacobi stencil",
+        }
+    }
+
+    /// Unit of the throughput. The SpMV and stencil kernels are limited by the memory
+    /// bandwidth, so they are measured in bytes.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            NumericKernel::NBody | NumericKernel::Fft => "GFLOP/s",
+            NumericKernel::SpMV | NumericKernel::Stencil => "GB/s",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NumericKernelResult {
+    pub kernel: NumericKernel,
+    pub threads: usize,
+    pub duration: Duration,
+    pub flops: u64,
+    /// Bytes the kernel reads and writes, counting each array once per pass.
+    pub bytes: u64,
+    /// GFLOP/s or GB/s, see `NumericKernel::unit`.
+    pub throughput: f64,
+    /// Deviation of the result from the expected one.
+    pub error: f64,
+    pub tolerance: f64,
+    /// Whether `error` is within `tolerance`. Invalid kernels are left out of the
+    /// workload score.
+    pub valid: bool,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NumericResult {
+    pub duration: Duration,
+    pub threads: usize,
+    pub single_thread: Vec<NumericKernelResult>,
+    pub multi_thread: Vec<NumericKernelResult>,
+    /// Geometric mean of the scores of the valid kernels of both variants, `None` when
+    /// no kernel is valid.
+    pub score: Option<f64>,
+}
+
+/// What a kernel run did, before the throughput is computed.
+struct Run {
+    duration: Duration,
+    flops: u64,
+    bytes: u64,
+    error: f64,
+}
+
+struct Bodies {
+    position: Vec<[f64; 3]>,
+    velocity: Vec<[f64; 3]>,
+    mass: Vec<f64>,
+}
+
+impl Bodies {
+    /// Bodies of equal mass spread uniformly over a unit ball, with small random
+    /// velocities and no net momentum.
+    fn generate() -> Self {
+        let mut rng = corpus::rng(6);
+        let mut position = Vec::with_capacity(BODIES);
+        while position.len() < BODIES {
+            let point: [f64; 3] = [
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            ];
+            if point.iter().map(|x| x * x).sum::<f64>() <= 1.0 {
+                position.push(point);
+            }
+        }
+        let mut velocity: Vec<[f64; 3]> = (0..BODIES)
+            .map(|_| [0; 3].map(|_| rng.gen_range(-0.1..0.1)))
+            .collect();
+        for axis in 0..3 {
+            let mean = velocity.iter().map(|v| v[axis]).sum::<f64>() / BODIES as f64;
+            velocity.iter_mut().for_each(|v| v[axis] -= mean);
+        }
+        Bodies {
+            position,
+            velocity,
+            mass: vec![1.0 / BODIES as f64; BODIES],
+        }
+    }
+
+    /// Kinetic plus softened potential energy, conserved by the integration.
+    fn energy(&self) -> f64 {
+        let mut energy = 0.0;
+        for i in 0..BODIES {
+            let speed2: f64 = self.velocity[i].iter().map(|v| v * v).sum();
+            energy += 0.5 * self.mass[i] * speed2;
+            for j in i + 1..BODIES {
+                let r2: f64 = (0..3)
+                    .map(|axis| (self.position[j][axis] - self.position[i][axis]).powi(2))
+                    .sum();
+                energy -= self.mass[i] * self.mass[j] / (r2 + SOFTENING * SOFTENING).sqrt();
+            }
+        }
+        energy
+    }
+
+    /// Accelerations of every body, computed in parallel one body per task.
+    fn accelerations(&self, acceleration: &mut [[f64; 3]]) {
+        let (position, mass) = (&self.position, &self.mass);
+        acceleration
+            .par_iter_mut()
+            .zip(position.par_iter())
+            .for_each(|(acceleration, [xi, yi, zi])| {
+                let (mut ax, mut ay, mut az) = (0.0, 0.0, 0.0);
+                // The body itself is at distance zero and adds nothing
+                for ([xj, yj, zj], mj) in position.iter().zip(mass) {
+                    let (dx, dy, dz) = (xj - xi, yj - yi, zj - zi);
+                    let r2 = dx * dx + dy * dy + dz * dz + SOFTENING * SOFTENING;
+                    let inverse = 1.0 / r2.sqrt();
+                    let strength = mj * inverse * inverse * inverse;
+                    ax += dx * strength;
+                    ay += dy * strength;
+                    az += dz * strength;
+                }
+                *acceleration = [ax, ay, az];
+            });
+    }
+
+    /// Half a velocity update with `acceleration`.
+    fn kick(&mut self, acceleration: &[[f64; 3]]) {
+        for (velocity, acceleration) in self.velocity.iter_mut().zip(acceleration) {
+            for axis in 0..3 {
+                velocity[axis] += acceleration[axis] * TIME_STEP / 2.0;
+            }
+        }
+    }
+
+    /// One leapfrog step, `acceleration` holds the accelerations at the current
+    /// positions before and the ones at the new positions after.
+    fn step(&mut self, acceleration: &mut [[f64; 3]]) {
+        self.kick(acceleration);
+        for (position, velocity) in self.position.iter_mut().zip(&self.velocity) {
+            for axis in 0..3 {
+                position[axis] += velocity[axis] * TIME_STEP;
+            }
+        }
+        self.accelerations(acceleration);
+        self.kick(acceleration);
+    }
+}
+
+/// Steps the bodies for about `flops` operations. The error is the relative energy
+/// drift.
+fn run_nbody(flops: u64) -> Run {
+    let mut bodies = Bodies::generate();
+    let initial_energy = bodies.energy();
+    let mut acceleration = vec![[0.0; 3]; BODIES];
+    bodies.accelerations(&mut acceleration);
+    let flops_per_step = FLOPS_PER_INTERACTION * (BODIES * BODIES) as u64;
+    let steps = flops.div_ceil(flops_per_step).max(1);
+
+    let start = Instant::now();
+    for _ in 0..steps {
+        bodies.step(&mut acceleration);
+    }
+    let duration = start.elapsed();
+
+    Run {
+        duration,
+        flops: steps * flops_per_step,
+        // Positions read and written, velocities read and written twice, accelerations
+        // written and read twice
+        bytes: steps * (BODIES * (9 * size_of::<[f64; 3]>() + size_of::<f64>())) as u64,
+        error: ((bodies.energy() - initial_energy) / initial_energy).abs(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn add(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re - other.re,
+            im: self.im - other.im,
+        }
+    }
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+/// Twiddle factors and bit reversal permutation of an FFT of a power of two of points.
+struct FftPlan {
+    twiddles: Vec<Complex>,
+    reversed: Vec<usize>,
+}
+
+impl FftPlan {
+    fn new(length: usize) -> Self {
+        let bits = length.trailing_zeros();
+        FftPlan {
+            twiddles: (0..length / 2)
+                .map(|k| {
+                    let angle = -2.0 * PI * k as f64 / length as f64;
+                    Complex {
+                        re: angle.cos(),
+                        im: angle.sin(),
+                    }
+                })
+                .collect(),
+            reversed: (0..length)
+                .map(|i| i.reverse_bits() >> (usize::BITS - bits))
+                .collect(),
+        }
+    }
+
+    /// Iterative in-place radix-2 transform, unscaled in both directions.
+    fn transform(&self, data: &mut [Complex], inverse: bool) {
+        for (i, j) in self.reversed.iter().enumerate() {
+            if i < *j {
+                data.swap(i, *j);
+            }
+        }
+        let length = self.reversed.len();
+        let mut size = 2;
+        while size <= length {
+            let half = size / 2;
+            let stride = length / size;
+            for block in data.chunks_exact_mut(size) {
+                let (low, high) = block.split_at_mut(half);
+                for (k, (a, b)) in low.iter_mut().zip(high).enumerate() {
+                    let mut twiddle = self.twiddles[k * stride];
+                    if inverse {
+                        twiddle.im = -twiddle.im;
+                    }
+                    let product = b.mul(twiddle);
+                    *b = a.sub(product);
+                    *a = a.add(product);
+                }
+            }
+            size *= 2;
+        }
+    }
+}
+
+/// Transforms a random signal back and forth, one pair of transforms per task, for
+/// about `flops` operations. The error is the largest difference with the signal.
+fn run_fft(flops: u64) -> Run {
+    let plan = FftPlan::new(FFT_LENGTH);
+    let mut rng = corpus::rng(7);
+    let signal: Vec<Complex> = (0..FFT_LENGTH)
+        .map(|_| Complex {
+            re: rng.gen_range(-1.0..1.0),
+            im: rng.gen_range(-1.0..1.0),
+        })
+        .collect();
+    // 5 N log2 N, the usual count for a radix-2 transform
+    let passes = FFT_LENGTH.trailing_zeros() as u64;
+    let flops_per_transform = 5 * FFT_LENGTH as u64 * passes;
+    let pairs = flops.div_ceil(2 * flops_per_transform).max(1);
+
+    let start = Instant::now();
+    let error = (0..pairs)
+        .into_par_iter()
+        .map_init(
+            || vec![Complex::default(); FFT_LENGTH],
+            |data, _| {
+                data.copy_from_slice(&signal);
+                plan.transform(data, false);
+                plan.transform(data, true);
+                let scale = 1.0 / FFT_LENGTH as f64;
+                data.iter()
+                    .zip(&signal)
+                    .map(|(value, expected)| {
+                        (value.re * scale - expected.re)
+                            .abs()
+                            .max((value.im * scale - expected.im).abs())
+                    })
+                    .fold(0.0, f64::max)
+            },
+        )
+        .reduce(|| 0.0, f64::max);
+    let duration = start.elapsed();
+
+    Run {
+        duration,
+        flops: 2 * pairs * flops_per_transform,
+        // Every butterfly pass reads and writes the whole signal
+        bytes: 2 * pairs * 2 * passes * (FFT_LENGTH * size_of::<Complex>()) as u64,
+        error,
+    }
+}
+
+/// Sparse matrix in compressed sparse row format, with the sum of each column to check
+/// the products.
+struct CsrMatrix {
+    row_offsets: Vec<usize>,
+    columns: Vec<u32>,
+    values: Vec<f64>,
+    column_sums: Vec<f64>,
+}
+
+impl CsrMatrix {
+    /// Positive entries at random columns of a band around the diagonal, like the
+    /// matrix of a mesh with its nodes numbered by locality.
+    fn generate() -> Self {
+        let mut rng = corpus::rng(8);
+        let mut row_offsets = Vec::with_capacity(SPARSE_ROWS + 1);
+        let mut columns = Vec::with_capacity(SPARSE_ROWS * ROW_ENTRIES);
+        let mut values = Vec::with_capacity(SPARSE_ROWS * ROW_ENTRIES);
+        let mut column_sums = vec![0.0; SPARSE_ROWS];
+        row_offsets.push(0);
+        for row in 0..SPARSE_ROWS {
+            let first = row.saturating_sub(SPARSE_BAND / 2);
+            let last = (first + SPARSE_BAND).min(SPARSE_ROWS);
+            let mut row_columns: Vec<u32> = (0..ROW_ENTRIES)
+                .map(|_| rng.gen_range(first as u32..last as u32))
+                .collect();
+            row_columns.sort_unstable();
+            for column in row_columns {
+                let value = rng.gen_range(0.5..1.5);
+                columns.push(column);
+                values.push(value);
+                column_sums[column as usize] += value;
+            }
+            row_offsets.push(columns.len());
+        }
+        CsrMatrix {
+            row_offsets,
+            columns,
+            values,
+            column_sums,
+        }
+    }
+
+    fn multiply(&self, x: &[f64], y: &mut [f64]) {
+        y.par_chunks_mut(ROWS_PER_TASK)
+            .enumerate()
+            .for_each(|(task, rows)| {
+                for (offset, y) in rows.iter_mut().enumerate() {
+                    let row = task * ROWS_PER_TASK + offset;
+                    let entries = self.row_offsets[row]..self.row_offsets[row + 1];
+                    *y = self.columns[entries.clone()]
+                        .iter()
+                        .zip(&self.values[entries])
+                        .map(|(column, value)| value * x[*column as usize])
+                        .sum();
+                }
+            });
+    }
+}
+
+/// Multiplies the matrix by a vector for about `flops` operations. The error compares
+/// the sum of the product with the one given by the column sums.
+fn run_spmv(flops: u64) -> Run {
+    let matrix = CsrMatrix::generate();
+    let x: Vec<f64> = (0..SPARSE_ROWS)
+        .map(|column| 1.0 + (column % 7) as f64 * 0.25)
+        .collect();
+    let mut y = vec![0.0; SPARSE_ROWS];
+    let entries = matrix.values.len() as u64;
+    let products = flops.div_ceil(2 * entries).max(1);
+
+    let start = Instant::now();
+    for _ in 0..products {
+        matrix.multiply(black_box(&x), &mut y);
+        black_box(&y);
+    }
+    let duration = start.elapsed();
+
+    let sum: f64 = y.iter().sum();
+    let expected: f64 = matrix.column_sums.iter().zip(&x).map(|(s, x)| s * x).sum();
+    let bytes_per_product = entries * (size_of::<f64>() + size_of::<u32>()) as u64
+        + (matrix.row_offsets.len() * size_of::<usize>()) as u64
+        + 2 * (SPARSE_ROWS * size_of::<f64>()) as u64;
+    Run {
+        duration,
+        flops: products * 2 * entries,
+        bytes: products * bytes_per_product,
+        error: ((sum - expected) / expected).abs(),
+    }
+}
+
+/// One Jacobi sweep of the Laplace equation, the boundary of `next` is left as it is.
+fn jacobi_sweep(grid: &[f64], next: &mut [f64]) {
+    next.par_chunks_mut(GRID_SIZE)
+        .enumerate()
+        .skip(1)
+        .take(GRID_SIZE - 2)
+        .for_each(|(i, row)| {
+            let (up, center, down) = (
+                &grid[(i - 1) * GRID_SIZE..i * GRID_SIZE],
+                &grid[i * GRID_SIZE..(i + 1) * GRID_SIZE],
+                &grid[(i + 1) * GRID_SIZE..(i + 2) * GRID_SIZE],
+            );
+            for j in 1..GRID_SIZE - 1 {
+                row[j] = 0.25 * (up[j] + down[j] + center[j - 1] + center[j + 1]);
+            }
+        });
+}
+
+/// Relaxes a grid heated on its top edge for about `flops` operations. The solution is
+/// symmetric and between 0 and 1, the error is its largest deviation from both.
+fn run_stencil(flops: u64) -> Run {
+    let mut grid = vec![0.0; GRID_SIZE * GRID_SIZE];
+    grid[..GRID_SIZE].fill(1.0);
+    let mut next = grid.clone();
+    let points = ((GRID_SIZE - 2) * (GRID_SIZE - 2)) as u64;
+    let sweeps = flops.div_ceil(4 * points).max(1);
+
+    let start = Instant::now();
+    for _ in 0..sweeps {
+        jacobi_sweep(black_box(&grid), &mut next);
+        std::mem::swap(&mut grid, &mut next);
+    }
+    let duration = start.elapsed();
+
+    let error = grid
+        .chunks_exact(GRID_SIZE)
+        .flat_map(|row| {
+            row.iter().zip(row.iter().rev()).map(|(value, mirrored)| {
+                let outside = (-value).max(value - 1.0).max(0.0);
+                (value - mirrored).abs().max(outside)
+            })
+        })
+        .fold(0.0, f64::max);
+    Run {
+        duration,
+        flops: sweeps * 4 * points,
+        bytes: sweeps * 2 * (GRID_SIZE * GRID_SIZE * size_of::<f64>()) as u64,
+        error,
+    }
+}
+
+/// Largest error accepted in the result of each kernel.
+fn tolerance(kernel: NumericKernel) -> f64 {
+    match kernel {
+        NumericKernel::NBody => 1e-6,
+        NumericKernel::Fft | NumericKernel::SpMV => 1e-9,
+        NumericKernel::Stencil => 1e-12,
+    }
+}
+
+fn measure(kernel: NumericKernel, flops: u64, pool: &rayon::ThreadPool) -> NumericKernelResult {
+    let run = pool.install(|| match kernel {
+        NumericKernel::NBody => run_nbody(flops),
+        NumericKernel::Fft => run_fft(flops),
+        NumericKernel::SpMV => run_spmv(flops),
+        NumericKernel::Stencil => run_stencil(flops),
+    });
+    let tolerance = tolerance(kernel);
+
+    let threads = pool.current_num_threads();
+    let seconds = run.duration.as_secs_f64();
+    let amount = match kernel {
+        NumericKernel::NBody | NumericKernel::Fft => run.flops,
+        NumericKernel::SpMV | NumericKernel::Stencil => run.bytes,
+    };
+    let throughput = if seconds > 0.0 {
+        amount as f64 / 1e9 / seconds
+    } else {
+        0.0
+    };
+    NumericKernelResult {
+        kernel,
+        threads,
+        duration: run.duration,
+        flops: run.flops,
+        bytes: run.bytes,
+        throughput,
+        error: run.error,
+        tolerance,
+        // Also false when the error is NaN
+        valid: run.error <= tolerance,
+        score: scoring::throughput_score(throughput, scoring::reference_numeric_throughput(kernel)),
+    }
+}
+
+/// Runs every kernel for about `flops` floating point operations, first on one thread
+/// and then on a pool of `num_threads`.
+pub fn benchmark_numeric(flops: u64, num_threads: usize) -> NumericResult {
+    let single_pool = super::thread_pool(1);
+    let pool = super::thread_pool(num_threads);
+
+    let start = Instant::now();
+    let single_thread: Vec<NumericKernelResult> = NumericKernel::ALL
+        .iter()
+        .map(|kernel| measure(*kernel, flops, &single_pool))
+        .collect();
+    let multi_thread: Vec<NumericKernelResult> = NumericKernel::ALL
+        .iter()
+        .map(|kernel| measure(*kernel, flops, &pool))
+        .collect();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = single_thread
+        .iter()
+        .chain(&multi_thread)
+        .filter(|result| result.valid)
+        .map(|result| result.score)
+        .collect();
+    NumericResult {
+        duration,
+        threads: pool.current_num_threads(),
+        single_thread,
+        multi_thread,
+        score: scoring::composite_score(&scores),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels_serialize_with_stable_names() {
+        let names: Vec<String> = NumericKernel::ALL
+            .iter()
+            .map(|kernel| serde_json::to_string(kernel).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [r#""n-body""#, r#""fft""#, r#""spmv""#, r#""stencil""#]
+        );
+    }
+
+    #[test]
+    fn fft_round_trips_a_short_signal() {
+        const LENGTH: usize = 16;
+        let plan = FftPlan::new(LENGTH);
+        let signal: Vec<Complex> = (0..LENGTH)
+            .map(|i| Complex {
+                re: (i as f64).sin(),
+                im: (i % 3) as f64,
+            })
+            .collect();
+        let mut data = signal.clone();
+        plan.transform(&mut data, false);
+        // The first point is the sum of the signal
+        let sum = signal.iter().fold(Complex::default(), |sum, x| sum.add(*x));
+        assert!((data[0].re - sum.re).abs() < 1e-12 && (data[0].im - sum.im).abs() < 1e-12);
+
+        plan.transform(&mut data, true);
+        for (value, expected) in data.iter().zip(&signal) {
+            assert!((value.re / LENGTH as f64 - expected.re).abs() < 1e-12);
+            assert!((value.im / LENGTH as f64 - expected.im).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn fft_of_an_impulse_is_flat() {
+        let plan = FftPlan::new(8);
+        let mut data = vec![Complex::default(); 8];
+        data[0].re = 1.0;
+        plan.transform(&mut data, false);
+        assert!(data
+            .iter()
+            .all(|value| *value == Complex { re: 1.0, im: 0.0 }));
+    }
+
+    #[test]
+    fn every_kernel_is_within_tolerance() {
+        let pool = crate::benchmark::thread_pool(1);
+        for kernel in NumericKernel::ALL {
+            let result = measure(*kernel, 1, &pool);
+            assert!(
+                result.valid,
+                "{}: error {} above {}",
+                kernel.name(),
+                result.error,
+                result.tolerance
+            );
+        }
+    }
+}
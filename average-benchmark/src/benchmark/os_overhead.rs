@@ -0,0 +1,314 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use std::{
+    ffi::CString,
+    fs::File,
+    hint::black_box,
+    io::{self, Read, Write},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    ptr,
+    sync::atomic::{AtomicU32, Ordering},
+    thread,
+};
+
+// A null syscall takes from tens to hundreds of nanoseconds depending on the
+// mitigations, a context switch microseconds and creating a process a millisecond, each
+// kernel does this fraction of the profile's operations.
+const SYSCALL_DIVISOR: u64 = 100;
+const SWITCH_DIVISOR: u64 = 2_000;
+const PROCESS_DIVISOR: u64 = 1_000_000;
+
+// Programs run by the fork, exec and wait kernel, the first one that exists.
+#[cfg(target_os = "linux")]
+const TRUE_PROGRAMS: &[&str] = &["/bin/true", "/usr/bin/true"];
+
+/// Kernels of the OS overhead workload, each one reported in nanoseconds per operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OsKernel {
+    /// `getppid` called with `syscall`, so the C library cannot cache it.
+    Syscall,
+    /// A byte sent back and forth over two pipes between two threads, per switch.
+    PipeThreads,
+    /// The same between this process and a forked child.
+    PipeProcesses,
+    /// `fork` of this process, `execve` of `true` and `waitpid` until it exits.
+    ForkExecWait,
+    /// A futex word handed back and forth between two threads with `FUTEX_WAKE`, per
+    /// wake.
+    FutexWake,
+}
+
+impl OsKernel {
+    pub const ALL: &'static [OsKernel] = &[
+        OsKernel::Syscall,
+        OsKernel::PipeThreads,
+        OsKernel::PipeProcesses,
+        OsKernel::ForkExecWait,
+        OsKernel::FutexWake,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OsKernel::Syscall => "getppid syscall",
+            OsKernel::PipeThreads => "pipe context switch (threads)",
+            OsKernel::PipeProcesses => "pipe context switch (processes)",
+            OsKernel::ForkExecWait => "fork + exec + wait",
+            OsKernel::FutexWake => "futex wake",
+        }
+    }
+
+    fn divisor(&self) -> u64 {
+        match self {
+            OsKernel::Syscall => SYSCALL_DIVISOR,
+            OsKernel::PipeThreads | OsKernel::PipeProcesses | OsKernel::FutexWake => SWITCH_DIVISOR,
+            OsKernel::ForkExecWait => PROCESS_DIVISOR,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsKernelResult {
+    pub kernel: OsKernel,
+    /// Syscalls, context switches, processes or wakes.
+    pub operations: u64,
+    pub duration: Duration,
+    pub nanoseconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsOverheadResult {
+    pub duration: Duration,
+    /// Without the kernels that failed, e.g. `fork` in a sandbox. Empty outside of
+    /// Linux.
+    pub kernels: Vec<OsKernelResult>,
+}
+
+#[cfg(target_os = "linux")]
+fn pipe() -> Option<(File, File)> {
+    let mut fds = [0; 2];
+    // SAFETY: `pipe` writes two new descriptors to the array, owned by the files from
+    // then on.
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            return None;
+        }
+        Some((
+            File::from(OwnedFd::from_raw_fd(fds[0])),
+            File::from(OwnedFd::from_raw_fd(fds[1])),
+        ))
+    }
+}
+
+/// Sends a byte through `ping` and waits for it to come back through `pong`
+/// `round_trips` times, then closes `ping` so the other end stops.
+#[cfg(target_os = "linux")]
+fn ping_pong(mut ping: File, mut pong: File, round_trips: u64) -> Option<Duration> {
+    let mut byte = [0];
+    let start = Instant::now();
+    for _ in 0..round_trips {
+        ping.write_all(&byte).ok()?;
+        pong.read_exact(&mut byte).ok()?;
+    }
+    Some(start.elapsed())
+}
+
+#[cfg(target_os = "linux")]
+fn pipe_threads(round_trips: u64) -> Option<Duration> {
+    let (ping_read, ping_write) = pipe()?;
+    let (pong_read, pong_write) = pipe()?;
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            let (mut ping_read, mut pong_write) = (ping_read, pong_write);
+            let mut byte = [0];
+            while ping_read.read_exact(&mut byte).is_ok() && pong_write.write_all(&byte).is_ok() {}
+        });
+        ping_pong(ping_write, pong_read, round_trips)
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn pipe_processes(round_trips: u64) -> Option<Duration> {
+    let (ping_read, ping_write) = pipe()?;
+    let (pong_read, pong_write) = pipe()?;
+    // SAFETY: the child only makes async-signal-safe calls on descriptors it inherited
+    // and exits with `_exit`, other threads of this process don't exist in it.
+    let child = unsafe { libc::fork() };
+    match child {
+        -1 => return None,
+        0 => unsafe {
+            // Without its copy of the write end the child sees the end of the pings
+            libc::close(ping_write.as_raw_fd());
+            libc::close(pong_read.as_raw_fd());
+            let mut byte = 0u8;
+            while libc::read(ping_read.as_raw_fd(), ptr::addr_of_mut!(byte).cast(), 1) == 1
+                && libc::write(pong_write.as_raw_fd(), ptr::addr_of!(byte).cast(), 1) == 1
+            {
+            }
+            libc::_exit(0);
+        },
+        _ => {}
+    }
+    drop((ping_read, pong_write));
+    let duration = ping_pong(ping_write, pong_read, round_trips);
+    // SAFETY: waits for the child forked above.
+    unsafe { libc::waitpid(child, ptr::null_mut(), 0) };
+    duration
+}
+
+#[cfg(target_os = "linux")]
+fn fork_exec_wait(processes: u64) -> Option<Duration> {
+    let program = TRUE_PROGRAMS
+        .iter()
+        .find(|program| std::path::Path::new(program).exists())?;
+    // Prepared before forking, the child can't allocate
+    let path = CString::new(*program).ok()?;
+    let argv = [path.as_ptr(), ptr::null()];
+    let envp: [*const libc::c_char; 1] = [ptr::null()];
+
+    let start = Instant::now();
+    for _ in 0..processes {
+        // SAFETY: the child only calls `execve` and `_exit`, both async-signal-safe,
+        // with arguments that outlive the call.
+        let child = unsafe { libc::fork() };
+        match child {
+            -1 => return None,
+            0 => unsafe {
+                libc::execve(path.as_ptr(), argv.as_ptr(), envp.as_ptr());
+                libc::_exit(127);
+            },
+            _ => {}
+        }
+        let mut status = 0;
+        let waited = loop {
+            // SAFETY: waits for the child forked above.
+            let waited = unsafe { libc::waitpid(child, &mut status, 0) };
+            // Waits again when a signal handler interrupted it, the child is still running
+            if waited != -1 || io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                break waited;
+            }
+        };
+        if waited != child || !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+            return None;
+        }
+    }
+    Some(start.elapsed())
+}
+
+#[cfg(target_os = "linux")]
+fn futex_wait(word: &AtomicU32, expected: u32) {
+    // SAFETY: the futex word is a valid aligned u32 for the whole call, the kernel
+    // returns right away if it no longer holds `expected`.
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            ptr::null::<libc::timespec>(),
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn futex_wake(word: &AtomicU32) {
+    // SAFETY: as in `futex_wait`, waking has no other effect.
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            1,
+        );
+    }
+}
+
+/// Each thread in turn moves the word to the next value, wakes the other one and waits
+/// for its answer, `wakes` wakes in total.
+#[cfg(target_os = "linux")]
+fn futex_ping_pong(wakes: u64) -> Duration {
+    let round_trips = (wakes / 2) as u32;
+    let word = AtomicU32::new(0);
+    let handoff = |word: &AtomicU32, value: u32| {
+        word.store(value, Ordering::Release);
+        futex_wake(word);
+        loop {
+            let current = word.load(Ordering::Acquire);
+            if current != value {
+                break;
+            }
+            futex_wait(word, current);
+        }
+    };
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for round_trip in 0..round_trips {
+                while word.load(Ordering::Acquire) != 2 * round_trip + 1 {
+                    futex_wait(&word, 2 * round_trip);
+                }
+                // The last answer isn't waited for
+                if round_trip + 1 == round_trips {
+                    word.store(2 * round_trip + 2, Ordering::Release);
+                    futex_wake(&word);
+                } else {
+                    handoff(&word, 2 * round_trip + 2);
+                }
+            }
+        });
+        let start = Instant::now();
+        for round_trip in 0..round_trips {
+            handoff(&word, 2 * round_trip + 1);
+        }
+        start.elapsed()
+    })
+}
+
+/// Runs `kernel` for `operations` operations, `None` if it failed.
+#[cfg(target_os = "linux")]
+fn measure(kernel: OsKernel, operations: u64) -> Option<Duration> {
+    match kernel {
+        OsKernel::Syscall => {
+            let start = Instant::now();
+            for _ in 0..operations {
+                // SAFETY: getppid takes no arguments and can't fail.
+                black_box(unsafe { libc::syscall(libc::SYS_getppid) });
+            }
+            Some(start.elapsed())
+        }
+        // Two switches per round trip
+        OsKernel::PipeThreads => pipe_threads(operations / 2),
+        OsKernel::PipeProcesses => pipe_processes(operations / 2),
+        OsKernel::ForkExecWait => fork_exec_wait(operations),
+        OsKernel::FutexWake => Some(futex_ping_pong(operations)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn measure(_kernel: OsKernel, _operations: u64) -> Option<Duration> {
+    None
+}
+
+/// Runs every kernel of the OS overhead workload for its fraction of `operations`.
+pub fn benchmark_os_overhead(operations: u64) -> OsOverheadResult {
+    let start = Instant::now();
+    let kernels = OsKernel::ALL
+        .iter()
+        .filter_map(|kernel| {
+            // Whole round trips, at least a few of them
+            let operations = (operations / kernel.divisor()).max(10) / 2 * 2;
+            let duration = measure(*kernel, operations)?;
+            Some(OsKernelResult {
+                kernel: *kernel,
+                operations,
+                duration,
+                nanoseconds: duration.as_secs_f64() * 1e9 / operations as f64,
+            })
+        })
+        .collect();
+    OsOverheadResult {
+        duration: start.elapsed(),
+        kernels,
+    }
+}
@@ -0,0 +1,264 @@
+use rand::{Rng, RngCore};
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::corpus;
+use crate::system_info::{self, StorageInfo};
+
+// Size of the reads and writes of the sequential tests.
+const SEQUENTIAL_BLOCK: usize = 1024 * 1024;
+
+// Size and alignment of the random reads and writes and of the writes before each
+// fsync, the page size and the usual sector size.
+const RANDOM_BLOCK: usize = 4096;
+
+// The random tests do a sixteenth of the file's blocks and the fsync test a 64th, a
+// spinning disk takes milliseconds for each one so both also stop after this time.
+const RANDOM_DIVISOR: u64 = 16;
+const FSYNC_DIVISOR: u64 = 64;
+const TIME_LIMIT: Duration = Duration::from_secs(5);
+
+/// Sequential and random I/O on a scratch file.
+#[derive(Debug, Serialize)]
+pub struct StorageMeasurements {
+    /// Including the final fsync.
+    pub sequential_write_megabytes_per_second: f64,
+    /// With `O_DIRECT` or after dropping the file from the page cache.
+    pub sequential_read_megabytes_per_second: f64,
+    /// Whether the reads and the random writes bypassed the page cache with `O_DIRECT`.
+    /// Without it the file is dropped from the cache before reading.
+    pub direct_io: bool,
+    pub random_read_iops: f64,
+    /// `None` without `O_DIRECT`, the writes would only reach the page cache.
+    pub random_write_iops: Option<f64>,
+    pub fsync: FsyncLatency,
+}
+
+/// Time of `fsync` after writing a 4 KiB block.
+#[derive(Debug, Serialize)]
+pub struct FsyncLatency {
+    pub operations: usize,
+    pub median_microseconds: f64,
+    pub p99_microseconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageResult {
+    pub duration: Duration,
+    pub device: StorageInfo,
+    pub file_bytes: u64,
+    /// `None` when the scratch file couldn't be written, see `error`.
+    pub measurements: Option<StorageMeasurements>,
+    pub error: Option<String>,
+}
+
+/// Reads and writes at an offset, which the standard library only provides on Unix.
+/// Elsewhere every call fails and the workload reports the error.
+#[cfg(not(unix))]
+trait FileExt {
+    fn read_at(&self, _buffer: &mut [u8], _offset: u64) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn read_exact_at(&self, _buffer: &mut [u8], _offset: u64) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn write_all_at(&self, _buffer: &[u8], _offset: u64) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(not(unix))]
+impl FileExt for File {}
+
+/// Asks the kernel to drop the file from the page cache, so reads come from the disk.
+#[cfg(target_os = "linux")]
+fn drop_page_cache(file: &File) {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: only takes the descriptor of an open file and can't touch memory.
+    unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+}
+
+#[cfg(not(target_os = "linux"))]
+fn drop_page_cache(_file: &File) {}
+
+/// Opens the file with `O_DIRECT`, `None` when it or the filesystem doesn't support it.
+#[cfg(target_os = "linux")]
+fn open_direct(path: &Path) -> Option<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_direct(_path: &Path) -> Option<File> {
+    None
+}
+
+/// Creates the scratch file in `directory`, also opened with `O_DIRECT` when supported,
+/// and removes it right away. The open descriptors keep its data until they are closed,
+/// so nothing is left behind even if the workload fails or the process is killed.
+fn create_scratch_file(directory: &Path) -> io::Result<(File, Option<File>)> {
+    let path = directory.join(format!(".average-benchmark-{}.tmp", std::process::id()));
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    let direct = open_direct(&path);
+    fs::remove_file(&path)?;
+    Ok((file, direct))
+}
+
+fn megabytes_per_second(bytes: u64, duration: Duration) -> f64 {
+    let seconds = duration.as_secs_f64();
+    if seconds > 0.0 {
+        bytes as f64 / 1e6 / seconds
+    } else {
+        0.0
+    }
+}
+
+/// A block of `size` bytes of `buffer` aligned to `RANDOM_BLOCK`, as `O_DIRECT` needs.
+/// `buffer` has to be `RANDOM_BLOCK` bytes longer.
+fn aligned_block(buffer: &mut [u8], size: usize) -> &mut [u8] {
+    let offset = buffer.as_ptr().align_offset(RANDOM_BLOCK);
+    &mut buffer[offset..offset + size]
+}
+
+/// Reads or writes random blocks of the file until it has done `operations` of them or
+/// `TIME_LIMIT` has passed, returns the operations per second.
+fn random_io(file: &File, blocks: u64, operations: u64, write: bool) -> io::Result<f64> {
+    let mut buffer = vec![0u8; RANDOM_BLOCK * 2];
+    let block = aligned_block(&mut buffer, RANDOM_BLOCK);
+    let mut rng = corpus::rng(12);
+    rng.fill_bytes(block);
+
+    let start = Instant::now();
+    let mut done = 0;
+    while done < operations && start.elapsed() < TIME_LIMIT {
+        let position = rng.gen_range(0..blocks) * RANDOM_BLOCK as u64;
+        if write {
+            file.write_all_at(block, position)?;
+        } else {
+            file.read_exact_at(block, position)?;
+        }
+        done += 1;
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    Ok(if seconds > 0.0 {
+        done as f64 / seconds
+    } else {
+        0.0
+    })
+}
+
+fn fsync_latency(file: &File, blocks: u64, operations: u64) -> io::Result<FsyncLatency> {
+    let block = vec![1u8; RANDOM_BLOCK];
+    let mut latencies = Vec::new();
+    let start = Instant::now();
+    for operation in 0..operations {
+        if start.elapsed() >= TIME_LIMIT && !latencies.is_empty() {
+            break;
+        }
+        file.write_all_at(&block, operation % blocks * RANDOM_BLOCK as u64)?;
+        let sync = Instant::now();
+        file.sync_all()?;
+        latencies.push(sync.elapsed());
+    }
+    latencies.sort();
+    let microseconds = |quantile: f64| {
+        let index = ((latencies.len() - 1) as f64 * quantile).round() as usize;
+        latencies[index].as_secs_f64() * 1e6
+    };
+    Ok(FsyncLatency {
+        operations: latencies.len(),
+        median_microseconds: microseconds(0.5),
+        p99_microseconds: microseconds(0.99),
+    })
+}
+
+fn measure(
+    file: &mut File,
+    direct: Option<File>,
+    file_bytes: u64,
+) -> io::Result<StorageMeasurements> {
+    // Random data, so filesystems that compress can't shrink it
+    let mut buffer = vec![0u8; SEQUENTIAL_BLOCK + RANDOM_BLOCK];
+    let block = aligned_block(&mut buffer, SEQUENTIAL_BLOCK);
+    corpus::rng(12).fill_bytes(block);
+    let blocks = file_bytes / SEQUENTIAL_BLOCK as u64;
+
+    let start = Instant::now();
+    for _ in 0..blocks {
+        file.write_all(block)?;
+    }
+    file.sync_all()?;
+    let sequential_write = start.elapsed();
+
+    // Through `O_DIRECT` when possible, dropping the file from the cache is only a hint
+    drop_page_cache(file);
+    let read_file = direct.as_ref().unwrap_or(&*file);
+    let start = Instant::now();
+    let mut read = 0;
+    loop {
+        match read_file.read_at(block, read)? {
+            0 => break,
+            bytes => read += bytes as u64,
+        }
+    }
+    let sequential_read = start.elapsed();
+
+    let random_blocks = file_bytes / RANDOM_BLOCK as u64;
+    let operations = random_blocks / RANDOM_DIVISOR;
+    drop_page_cache(file);
+    let random_read_iops = random_io(read_file, random_blocks, operations, false)?;
+    let random_write_iops = direct
+        .as_ref()
+        .map(|direct| random_io(direct, random_blocks, operations, true))
+        .transpose()?;
+
+    Ok(StorageMeasurements {
+        sequential_write_megabytes_per_second: megabytes_per_second(
+            blocks * SEQUENTIAL_BLOCK as u64,
+            sequential_write,
+        ),
+        sequential_read_megabytes_per_second: megabytes_per_second(read, sequential_read),
+        direct_io: direct.is_some(),
+        random_read_iops,
+        random_write_iops,
+        fsync: fsync_latency(file, random_blocks, (random_blocks / FSYNC_DIVISOR).max(1))?,
+    })
+}
+
+/// Writes a scratch file of `bytes` bytes (whole MiB) in `directory`, reads it back and
+/// does random 4 KiB reads and writes and fsyncs on it.
+pub fn benchmark_storage(directory: &Path, bytes: usize) -> StorageResult {
+    let file_bytes = (bytes / SEQUENTIAL_BLOCK).max(1) as u64 * SEQUENTIAL_BLOCK as u64;
+    let start = Instant::now();
+    let measurements = create_scratch_file(directory)
+        .and_then(|(mut file, direct)| measure(&mut file, direct, file_bytes));
+    let (measurements, error) = match measurements {
+        Ok(measurements) => (Some(measurements), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    StorageResult {
+        duration: start.elapsed(),
+        device: system_info::get_storage_info(directory),
+        file_bytes,
+        measurements,
+        error,
+    }
+}
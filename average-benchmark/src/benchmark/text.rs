@@ -0,0 +1,287 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use super::corpus;
+use crate::scoring;
+
+// Bytes of each input. Every kernel goes over its input again and again until it has
+// processed the bytes of the profile, larger inputs would only make generating them
+// slower.
+const INPUT_SIZE: usize = 16 * 1024 * 1024;
+
+// Parsing is several times slower than hashing, so each kernel processes this fraction
+// of the profile's bytes to keep the workload about as long as the others.
+const BYTES_DIVISOR: usize = 2;
+
+// Searches of the regex kernel, the kind of patterns used to filter and mine logs.
+const PATTERNS: &[&str] = &[
+    // Server errors
+    r"status=5\d\d",
+    // Slow requests
+    r"latency=\d{4,}ms",
+    // Requests to a resource by identifier
+    r"(?:PUT|DELETE) /api/(?:users|orders)/\d+",
+    // Client addresses of a subnet
+    r"from 10\.1\d\.\d{1,3}\.\d{1,3}",
+    // Emails
+    r"[\w.+-]+@[\w-]+\.[\w.]+",
+    // Errors of a service, at the start of each line
+    r"(?m)^\S+ ERROR \[billing\]",
+];
+
+/// Kernels of the text workload, each one scored in MB/s of input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextKernel {
+    /// Deserializing newline delimited JSON log records into a struct with serde_json.
+    JsonParse,
+    /// Validating UTF-8 text with non-ASCII words.
+    Utf8Validate,
+    /// Transcoding the same text to UTF-16 and back.
+    Utf8Transcode,
+    /// Splitting CSV rows with quoted fields into records with the csv crate.
+    CsvTokenize,
+    /// Searching access logs with several patterns of the regex crate.
+    Regex,
+}
+
+impl TextKernel {
+    pub const ALL: &'static [TextKernel] = &[
+        TextKernel::JsonParse,
+        TextKernel::Utf8Validate,
+        TextKernel::Utf8Transcode,
+        TextKernel::CsvTokenize,
+        TextKernel::Regex,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextKernel::JsonParse => "JSON parse",
+            TextKernel::Utf8Validate => "UTF-8 validate",
+            TextKernel::Utf8Transcode => "UTF-8 to UTF-16 and back",
+            TextKernel::CsvTokenize => "CSV tokenize",
+            TextKernel::Regex => "regex",
+        }
+    }
+
+    fn input(&self) -> Vec<u8> {
+        match self {
+            TextKernel::JsonParse => corpus::json(INPUT_SIZE),
+            TextKernel::Utf8Validate | TextKernel::Utf8Transcode => corpus::text(INPUT_SIZE),
+            TextKernel::CsvTokenize => corpus::csv(INPUT_SIZE),
+            TextKernel::Regex => corpus::logs(INPUT_SIZE),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TextKernelResult {
+    pub kernel: TextKernel,
+    pub bytes: usize,
+    pub duration: Duration,
+    pub megabytes_per_second: f64,
+    /// Records, UTF-16 code units or matches found in the input, the same on every
+    /// machine.
+    pub checksum: u64,
+    /// Whether every pass parsed its input and the transcoding gave back the text.
+    /// Invalid kernels are left out of the workload score.
+    pub valid: bool,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TextResult {
+    pub duration: Duration,
+    pub kernels: Vec<TextKernelResult>,
+    /// Geometric mean of the scores of the valid kernels, `None` when no kernel is valid.
+    pub score: Option<f64>,
+}
+
+/// A record of the JSON corpus, borrowing its strings from the input.
+#[derive(Deserialize)]
+struct LogRecord<'a> {
+    id: u64,
+    timestamp: u64,
+    #[serde(borrow)]
+    user: &'a str,
+    event: &'a str,
+    latency_ms: f64,
+    success: bool,
+    tags: Vec<&'a str>,
+}
+
+/// One pass of `kernel` over `input`, returning its checksum. `None` when the input
+/// doesn't parse or the transcoding changes the text.
+fn process(kernel: TextKernel, input: &[u8], patterns: &[Regex]) -> Option<u64> {
+    match kernel {
+        TextKernel::JsonParse => {
+            let mut records = 0;
+            for record in serde_json::Deserializer::from_slice(input).into_iter::<LogRecord>() {
+                let record = record.ok()?;
+                black_box((
+                    record.id,
+                    record.timestamp,
+                    record.user,
+                    record.event,
+                    record.latency_ms,
+                    record.success,
+                    record.tags,
+                ));
+                records += 1;
+            }
+            Some(records)
+        }
+        TextKernel::Utf8Validate => Some(std::str::from_utf8(black_box(input)).ok()?.len() as u64),
+        TextKernel::Utf8Transcode => {
+            let text = std::str::from_utf8(input).ok()?;
+            let utf16: Vec<u16> = text.encode_utf16().collect();
+            let utf8 = String::from_utf16(black_box(&utf16)).ok()?;
+            (utf8 == text).then_some(utf16.len() as u64)
+        }
+        TextKernel::CsvTokenize => {
+            let mut reader = csv::ReaderBuilder::new().from_reader(input);
+            let mut record = csv::ByteRecord::new();
+            let mut records = 0;
+            while reader.read_byte_record(&mut record).ok()? {
+                black_box(&record);
+                records += 1;
+            }
+            Some(records)
+        }
+        TextKernel::Regex => {
+            let logs = std::str::from_utf8(input).ok()?;
+            Some(
+                patterns
+                    .iter()
+                    .map(|pattern| pattern.find_iter(logs).count() as u64)
+                    .sum(),
+            )
+        }
+    }
+}
+
+fn run_kernel(kernel: TextKernel, bytes: usize, patterns: &[Regex]) -> TextKernelResult {
+    let input = kernel.input();
+    let passes = bytes.div_ceil(input.len()).max(1);
+
+    let start = Instant::now();
+    let mut checksum = None;
+    let mut valid = true;
+    for _ in 0..passes {
+        checksum = process(kernel, black_box(&input), patterns);
+        valid &= checksum.is_some();
+    }
+    let duration = start.elapsed();
+
+    let bytes = passes * input.len();
+    let megabytes_per_second = scoring::megabytes_per_second(bytes, duration);
+    TextKernelResult {
+        kernel,
+        bytes,
+        duration,
+        megabytes_per_second,
+        checksum: checksum.unwrap_or_default(),
+        valid,
+        score: scoring::throughput_score(
+            megabytes_per_second,
+            scoring::reference_text_throughput(kernel),
+        ),
+    }
+}
+
+/// Runs every kernel of the text workload over its generated input. Each kernel
+/// processes half of `bytes`, the regex kernel searches them with every pattern.
+pub fn benchmark_text(bytes: usize) -> TextResult {
+    let patterns: Vec<Regex> = PATTERNS
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("valid pattern"))
+        .collect();
+
+    let start = Instant::now();
+    let kernels: Vec<TextKernelResult> = TextKernel::ALL
+        .iter()
+        .map(|kernel| run_kernel(*kernel, bytes / BYTES_DIVISOR, &patterns))
+        .collect();
+    let duration = start.elapsed();
+
+    let scores: Vec<f64> = kernels
+        .iter()
+        .filter(|result| result.valid)
+        .map(|result| result.score)
+        .collect();
+    TextResult {
+        duration,
+        kernels,
+        score: scoring::composite_score(&scores),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns() -> Vec<Regex> {
+        PATTERNS
+            .iter()
+            .map(|pattern| Regex::new(pattern).unwrap())
+            .collect()
+    }
+
+    fn small_input(kernel: TextKernel) -> Vec<u8> {
+        const SIZE: usize = 64 * 1024;
+        match kernel {
+            TextKernel::JsonParse => corpus::json(SIZE),
+            TextKernel::Utf8Validate | TextKernel::Utf8Transcode => corpus::text(SIZE),
+            TextKernel::CsvTokenize => corpus::csv(SIZE),
+            TextKernel::Regex => corpus::logs(SIZE),
+        }
+    }
+
+    // Records, code units and matches of a valid pass, the same on every machine
+    #[test]
+    fn checksums_are_pinned() {
+        let patterns = patterns();
+        let checksums: Vec<(TextKernel, Option<u64>)> = TextKernel::ALL
+            .iter()
+            .map(|kernel| (*kernel, process(*kernel, &small_input(*kernel), &patterns)))
+            .collect();
+        assert_eq!(
+            checksums,
+            [
+                (TextKernel::JsonParse, Some(517)),
+                (TextKernel::Utf8Validate, Some(65540)),
+                (TextKernel::Utf8Transcode, Some(62553)),
+                (TextKernel::CsvTokenize, Some(950)),
+                (TextKernel::Regex, Some(778)),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_pattern_matches_the_logs() {
+        let logs = String::from_utf8(small_input(TextKernel::Regex)).unwrap();
+        for pattern in patterns() {
+            assert!(pattern.is_match(&logs), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn broken_input_is_invalid() {
+        let patterns = patterns();
+        let mut json = small_input(TextKernel::JsonParse);
+        json.truncate(json.len() - 10);
+        assert_eq!(process(TextKernel::JsonParse, &json, &patterns), None);
+
+        let mut text = small_input(TextKernel::Utf8Validate);
+        text.push(0xFF);
+        for kernel in [
+            TextKernel::Utf8Validate,
+            TextKernel::Utf8Transcode,
+            TextKernel::Regex,
+        ] {
+            assert_eq!(process(kernel, &text, &patterns), None);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::consent::Consent;
+use crate::privacy::PrivacyLevel;
+use crate::report::OutputFormat;
+use average_benchmark::benchmark::Workload;
+use average_benchmark::profile::Profile;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub overrides: ConfigOverrides,
+
+    /// Language of the messages (`es` or `en`). Defaults to the one in `LANG`.
+    #[arg(long, global = true, env = "AVERAGE_BENCHMARK_LANG")]
+    pub lang: Option<String>,
+
+    /// Sends the results without asking.
+    #[arg(short, long, global = true, conflicts_with = "no")]
+    pub yes: bool,
+
+    /// Doesn't send the results and doesn't ask.
+    #[arg(long, global = true)]
+    pub no: bool,
+}
+
+impl Cli {
+    /// The consent given with `--yes` or `--no`, which takes precedence over the config.
+    pub fn consent(&self) -> Option<Consent> {
+        if self.yes {
+            Some(Consent::Always)
+        } else if self.no {
+            Some(Consent::Never)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Runs the benchmark (default command).
+    Run(RunArgs),
+    /// Prints the effective configuration after applying every layer.
+    Config,
+    /// Sends the submissions saved in the outbox after a failed upload.
+    UploadPending,
+    /// Generates an ed25519 keypair to sign submissions sent to a local API.
+    Keygen,
+    /// Sends a payload previously saved with `run --export-payload`.
+    Submit {
+        /// JSON file written by `--export-payload`.
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Default, Args)]
+pub struct RunArgs {
+    /// Prints the payload that would be sent and exits without sending it.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Writes the payload that would be sent to FILE instead of sending it, so it can be
+    /// uploaded later with `average-benchmark submit FILE`.
+    #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+    pub export_payload: Option<PathBuf>,
+}
+
+/// Settings that override the config file. Each one can also be set through its
+/// environment variable, the command line flag takes precedence.
+#[derive(Debug, Args)]
+pub struct ConfigOverrides {
+    /// Config file to use instead of the one in the user's config directory.
+    #[arg(long, global = true, env = "AVERAGE_BENCHMARK_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Base URL of the API that receives the results.
+    #[arg(long, global = true, env = "AVERAGE_BENCHMARK_BACKEND_URL")]
+    pub backend_url: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "AVERAGE_BENCHMARK_PRIVACY_LEVEL"
+    )]
+    pub privacy_level: Option<PrivacyLevel>,
+
+    /// Payload fields that are never sent (comma separated).
+    #[arg(
+        long = "exclude-field",
+        global = true,
+        value_delimiter = ',',
+        env = "AVERAGE_BENCHMARK_EXCLUDED_FIELDS"
+    )]
+    pub excluded_fields: Option<Vec<String>>,
+
+    /// Run length: workloads, sizes, repetitions and telemetry.
+    #[arg(long, global = true, value_enum, env = "AVERAGE_BENCHMARK_PROFILE")]
+    pub profile: Option<Profile>,
+
+    /// Workloads to run instead of the ones of the profile (comma separated).
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_delimiter = ',',
+        env = "AVERAGE_BENCHMARK_WORKLOADS"
+    )]
+    pub workloads: Option<Vec<Workload>>,
+
+    /// Directory where the storage workload writes its scratch file. Setting it runs
+    /// the workload, which no profile includes.
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "AVERAGE_BENCHMARK_STORAGE_DIR"
+    )]
+    pub storage_dir: Option<PathBuf>,
+
+    #[arg(
+        long = "output",
+        global = true,
+        value_enum,
+        env = "AVERAGE_BENCHMARK_OUTPUT_FORMAT"
+    )]
+    pub output_format: Option<OutputFormat>,
+
+    /// Whether to send the results after a run. `--yes` and `--no` take precedence.
+    #[arg(long, global = true, value_enum, env = "AVERAGE_BENCHMARK_CONSENT")]
+    pub consent: Option<Consent>,
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::cli::ConfigOverrides;
+use crate::consent::Consent;
+use crate::network::NetworkConfig;
+use crate::privacy::PrivacyConfig;
+use crate::report::OutputFormat;
+use average_benchmark::benchmark::Workload;
+use average_benchmark::i18n::t;
+use average_benchmark::profile::Profile;
+
+/// Client settings, resolved in layers: built-in defaults, then `config.toml` in the
+/// user's config directory (`$XDG_CONFIG_HOME/average-benchmark/config.toml` on Linux),
+/// then environment variables and finally command line flags.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub backend_url: String,
+    pub output_format: OutputFormat,
+    pub profile: Profile,
+    /// Workloads to run instead of the ones of the profile.
+    pub workloads: Option<Vec<Workload>>,
+    /// Directory of the scratch file of the storage workload, which only runs when set
+    /// or selected in `workloads`.
+    pub storage_dir: Option<PathBuf>,
+    pub consent: Consent,
+    pub privacy: PrivacyConfig,
+    pub network: NetworkConfig,
+    /// Config file the settings were read from, or where they would be saved.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            backend_url: env!("BACKEND_URL").to_string(),
+            output_format: OutputFormat::default(),
+            profile: Profile::default(),
+            workloads: None,
+            storage_dir: None,
+            consent: Consent::default(),
+            privacy: PrivacyConfig::default(),
+            network: NetworkConfig::default(),
+            path: None,
+        }
+    }
+}
+
+impl Config {
+    fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(backend_url) = &overrides.backend_url {
+            self.backend_url = backend_url.clone();
+        }
+        if let Some(level) = overrides.privacy_level {
+            self.privacy.level = level;
+        }
+        if let Some(excluded_fields) = &overrides.excluded_fields {
+            self.privacy.excluded_fields = excluded_fields.clone();
+        }
+        if let Some(profile) = overrides.profile {
+            self.profile = profile;
+        }
+        if let Some(workloads) = &overrides.workloads {
+            self.workloads = Some(workloads.clone());
+        }
+        if let Some(storage_dir) = &overrides.storage_dir {
+            self.storage_dir = Some(storage_dir.clone());
+        }
+        if let Some(output_format) = overrides.output_format {
+            self.output_format = output_format;
+        }
+        if let Some(consent) = overrides.consent {
+            self.consent = consent;
+        }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("average-benchmark").join("config.toml"))
+}
+
+/// Loads the effective config. A missing default config file is not an error, but a
+/// file passed explicitly with `--config` must exist.
+pub fn load_config(overrides: &ConfigOverrides) -> Result<Config, Box<dyn std::error::Error>> {
+    let path = overrides.config.clone().or_else(config_path);
+
+    let mut config = match &path {
+        Some(path) if overrides.config.is_some() || path.exists() => {
+            let content = fs::read_to_string(path).map_err(|e| {
+                t!(
+                    "config-read-error",
+                    path = path.display().to_string(),
+                    error = e.to_string()
+                )
+            })?;
+            toml::from_str(&content).map_err(|e| {
+                t!(
+                    "config-invalid",
+                    path = path.display().to_string(),
+                    error = e.to_string()
+                )
+            })?
+        }
+        _ => Config::default(),
+    };
+
+    config.path = path;
+    config.apply_overrides(overrides);
+    Ok(config)
+}
+
+/// Stores the consent answer in the config file, keeping the rest of its settings.
+pub fn save_consent(path: &Path, consent: Consent) -> Result<(), Box<dyn std::error::Error>> {
+    let mut settings: toml::Table = match fs::read_to_string(path) {
+        Ok(content) => toml::from_str(&content).map_err(|e| {
+            t!(
+                "config-invalid",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+        })?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => {
+            return Err(t!(
+                "config-read-error",
+                path = path.display().to_string(),
+                error = e.to_string()
+            )
+            .into())
+        }
+    };
+    settings.insert("consent".to_string(), toml::Value::try_from(consent)?);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string_pretty(&settings)?).map_err(|e| {
+        t!(
+            "write-file-error",
+            path = path.display().to_string(),
+            error = e.to_string()
+        )
+        .into()
+    })
+}
+
+pub fn display_config(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    print!("{}", toml::to_string_pretty(config)?);
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{self, IsTerminal};
+
+use crate::utils::message;
+use average_benchmark::i18n::t;
+
+/// Whether the results are sent after a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Consent {
+    /// Asks every time. Without a terminal to ask on, the results are not sent.
+    #[default]
+    Ask,
+    /// Sends without asking.
+    Always,
+    /// Never sends.
+    Never,
+}
+
+/// Answer to the consent prompt. `Always` and `Never` are remembered in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Yes,
+    No,
+    Always,
+    Never,
+}
+
+impl Answer {
+    pub fn sends(self) -> bool {
+        matches!(self, Answer::Yes | Answer::Always)
+    }
+
+    /// The consent to store in the config file, if the answer asks to remember it.
+    pub fn remembered(self) -> Option<Consent> {
+        match self {
+            Answer::Always => Some(Consent::Always),
+            Answer::Never => Some(Consent::Never),
+            Answer::Yes | Answer::No => None,
+        }
+    }
+}
+
+/// Asks whether to send the results. Returns `None` when nobody can answer: stdin is not
+/// a terminal, was closed or can't be read.
+pub fn ask_to_send() -> Option<Answer> {
+    if !io::stdin().is_terminal() {
+        message!("{}", t!("stdin-not-terminal"));
+        return None;
+    }
+
+    message!("{}", t!("ask-to-send"));
+    message!("{}", t!("ask-to-send-note"));
+    let mut respuesta: String = String::new();
+
+    loop {
+        match io::stdin().read_line(&mut respuesta) {
+            // End of input, asking again would loop forever
+            Ok(0) => return None,
+            Ok(_) => match respuesta.trim().to_lowercase().as_str() {
+                "y" => return Some(Answer::Yes),
+                "n" => return Some(Answer::No),
+                "always" => return Some(Answer::Always),
+                "never" => return Some(Answer::Never),
+                _ => {
+                    message!("{}", t!("invalid-answer"));
+                    respuesta.clear();
+                }
+            },
+            Err(_) => {
+                message!("{}", t!("read-answer-error"));
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! C ABI of the library, declared in `c/average_benchmark.h` (generated with cbindgen).
+//!
+//! Results are allocated by the library and must be released with the matching
+//! `*_free` function. Functions return NULL instead of unwinding into the caller.
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::benchmark::Workload;
+use crate::profile::Profile;
+use crate::runner::{Benchmark, BenchmarkReport};
+
+/// Version of the C ABI, bumped on any incompatible change of the structs or functions.
+pub const AVERAGE_BENCHMARK_ABI_VERSION: u32 = 1;
+
+pub const AVERAGE_BENCHMARK_PROFILE_QUICK: u32 = 0;
+pub const AVERAGE_BENCHMARK_PROFILE_STANDARD: u32 = 1;
+pub const AVERAGE_BENCHMARK_PROFILE_EXTENDED: u32 = 2;
+
+pub const AVERAGE_BENCHMARK_WORKLOAD_SINGLE_THREAD: u32 = 1 << 0;
+pub const AVERAGE_BENCHMARK_WORKLOAD_MULTI_THREAD: u32 = 1 << 1;
+pub const AVERAGE_BENCHMARK_WORKLOAD_CRYPTO: u32 = 1 << 2;
+pub const AVERAGE_BENCHMARK_WORKLOAD_COMPRESSION: u32 = 1 << 3;
+pub const AVERAGE_BENCHMARK_WORKLOAD_INTEGER: u32 = 1 << 4;
+pub const AVERAGE_BENCHMARK_WORKLOAD_LATENCY: u32 = 1 << 5;
+pub const AVERAGE_BENCHMARK_WORKLOAD_NUMERIC: u32 = 1 << 6;
+pub const AVERAGE_BENCHMARK_WORKLOAD_TEXT: u32 = 1 << 7;
+pub const AVERAGE_BENCHMARK_WORKLOAD_ALLOCATOR: u32 = 1 << 8;
+pub const AVERAGE_BENCHMARK_WORKLOAD_ASYNC_RUNTIME: u32 = 1 << 9;
+pub const AVERAGE_BENCHMARK_WORKLOAD_OS_OVERHEAD: u32 = 1 << 10;
+/// Opt-in, writes a scratch file to the temporary directory.
+pub const AVERAGE_BENCHMARK_WORKLOAD_STORAGE: u32 = 1 << 11;
+
+/// Options of a run. A NULL pointer runs the standard profile.
+#[repr(C)]
+pub struct AverageBenchmarkOptions {
+    /// One of the `AVERAGE_BENCHMARK_PROFILE_*` constants.
+    pub profile: u32,
+    /// `AVERAGE_BENCHMARK_WORKLOAD_*` flags, 0 runs the workloads of the profile.
+    pub workloads: u32,
+    /// Threads of the multi-thread workloads, 0 uses the effective parallelism.
+    pub threads: u32,
+}
+
+/// Results of a run. Scores of workloads that did not run are NaN, the results of the
+/// workloads without a field here are in `json`.
+#[repr(C)]
+pub struct AverageBenchmarkResults {
+    pub profile_version: u32,
+    pub scoring_model_version: u32,
+    pub threads: u32,
+    pub score_single_thread: f64,
+    pub score_multi_thread: f64,
+    pub score_composite: f64,
+    pub single_thread_duration_ns: u64,
+    pub multi_thread_duration_ns: u64,
+    /// The full report as a JSON document, owned by the results.
+    pub json: *mut c_char,
+}
+
+fn profile_from_c(profile: u32) -> Option<Profile> {
+    match profile {
+        AVERAGE_BENCHMARK_PROFILE_QUICK => Some(Profile::Quick),
+        AVERAGE_BENCHMARK_PROFILE_STANDARD => Some(Profile::Standard),
+        AVERAGE_BENCHMARK_PROFILE_EXTENDED => Some(Profile::Extended),
+        _ => None,
+    }
+}
+
+fn workload_flag(workload: Workload) -> u32 {
+    match workload {
+        Workload::SingleThread => AVERAGE_BENCHMARK_WORKLOAD_SINGLE_THREAD,
+        Workload::MultiThread => AVERAGE_BENCHMARK_WORKLOAD_MULTI_THREAD,
+        Workload::Crypto => AVERAGE_BENCHMARK_WORKLOAD_CRYPTO,
+        Workload::Compression => AVERAGE_BENCHMARK_WORKLOAD_COMPRESSION,
+        Workload::Integer => AVERAGE_BENCHMARK_WORKLOAD_INTEGER,
+        Workload::Latency => AVERAGE_BENCHMARK_WORKLOAD_LATENCY,
+        Workload::Numeric => AVERAGE_BENCHMARK_WORKLOAD_NUMERIC,
+        Workload::Text => AVERAGE_BENCHMARK_WORKLOAD_TEXT,
+        Workload::Allocator => AVERAGE_BENCHMARK_WORKLOAD_ALLOCATOR,
+        Workload::AsyncRuntime => AVERAGE_BENCHMARK_WORKLOAD_ASYNC_RUNTIME,
+        Workload::OsOverhead => AVERAGE_BENCHMARK_WORKLOAD_OS_OVERHEAD,
+        Workload::Storage => AVERAGE_BENCHMARK_WORKLOAD_STORAGE,
+    }
+}
+
+/// Runs the suite, or returns `None` when the options are invalid or the run panics.
+///
+/// # Safety
+///
+/// `options` must be NULL or point to a valid `AverageBenchmarkOptions`.
+unsafe fn run(options: *const AverageBenchmarkOptions) -> Option<BenchmarkReport> {
+    let mut benchmark = Benchmark::new();
+    // SAFETY: the caller passes NULL or a valid pointer to the options
+    if let Some(options) = unsafe { options.as_ref() } {
+        benchmark = benchmark.profile(profile_from_c(options.profile)?);
+        if options.workloads != 0 {
+            let workloads: Vec<Workload> = Workload::ALL
+                .iter()
+                .chain(Workload::OPT_IN)
+                .copied()
+                .filter(|workload| options.workloads & workload_flag(*workload) != 0)
+                .collect();
+            benchmark = benchmark.workloads(&workloads);
+        }
+        if options.threads != 0 {
+            benchmark = benchmark.threads(options.threads as usize);
+        }
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(|| benchmark.run())).ok()
+}
+
+fn json_string(report: &BenchmarkReport) -> *mut c_char {
+    serde_json::to_string(report)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+#[no_mangle]
+pub extern "C" fn average_benchmark_abi_version() -> u32 {
+    AVERAGE_BENCHMARK_ABI_VERSION
+}
+
+/// Runs the suite and returns its results, to be released with
+/// `average_benchmark_results_free`. Returns NULL if the options are invalid.
+///
+/// # Safety
+///
+/// `options` must be NULL or point to a valid `AverageBenchmarkOptions`.
+#[no_mangle]
+pub unsafe extern "C" fn average_benchmark_run(
+    options: *const AverageBenchmarkOptions,
+) -> *mut AverageBenchmarkResults {
+    // SAFETY: forwarded from the caller
+    let Some(report) = (unsafe { run(options) }) else {
+        return ptr::null_mut();
+    };
+
+    let results = AverageBenchmarkResults {
+        profile_version: report.profile_version,
+        scoring_model_version: report.scoring_model_version,
+        threads: report.threads as u32,
+        score_single_thread: report
+            .single_thread
+            .as_ref()
+            .map_or(f64::NAN, |result| result.score),
+        score_multi_thread: report
+            .multi_thread
+            .as_ref()
+            .map_or(f64::NAN, |result| result.score),
+        score_composite: report.score_composite.unwrap_or(f64::NAN),
+        single_thread_duration_ns: report
+            .single_thread
+            .as_ref()
+            .map_or(0, |result| result.duration.as_nanos() as u64),
+        multi_thread_duration_ns: report
+            .multi_thread
+            .as_ref()
+            .map_or(0, |result| result.duration.as_nanos() as u64),
+        json: json_string(&report),
+    };
+    Box::into_raw(Box::new(results))
+}
+
+/// Releases results returned by `average_benchmark_run`. NULL is ignored.
+///
+/// # Safety
+///
+/// `results` must be NULL or a pointer returned by `average_benchmark_run` that was not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn average_benchmark_results_free(results: *mut AverageBenchmarkResults) {
+    if results.is_null() {
+        return;
+    }
+    // SAFETY: the pointer comes from `Box::into_raw` in `average_benchmark_run`
+    let results = unsafe { Box::from_raw(results) };
+    // SAFETY: `json` is NULL or comes from `CString::into_raw`
+    unsafe { average_benchmark_string_free(results.json) };
+}
+
+/// Runs the suite and returns the report as a JSON document, to be released with
+/// `average_benchmark_string_free`. Returns NULL if the options are invalid.
+///
+/// # Safety
+///
+/// `options` must be NULL or point to a valid `AverageBenchmarkOptions`.
+#[no_mangle]
+pub unsafe extern "C" fn average_benchmark_run_json(
+    options: *const AverageBenchmarkOptions,
+) -> *mut c_char {
+    // SAFETY: forwarded from the caller
+    match unsafe { run(options) } {
+        Some(report) => json_string(&report),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a string returned by the library. NULL is ignored.
+///
+/// # Safety
+///
+/// `string` must be NULL or a string returned by `average_benchmark_run_json` that was
+/// not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn average_benchmark_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the string comes from `CString::into_raw`
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
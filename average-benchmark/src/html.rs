@@ -0,0 +1,151 @@
+use serde_json::Value;
+
+use crate::report;
+use average_benchmark::i18n::{self, t};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse;margin-bottom:1.5em}\
+th,td{padding:.25em .5em;text-align:right}\
+.fields th{text-align:left}\
+.heatmap td{font-size:.8em;color:#000;min-width:2.5em}\
+.invalid{color:#c00}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Table of the fields of `payload` whose names `filter` accepts.
+fn fields_table(payload: &Value, filter: impl Fn(&str) -> bool) -> String {
+    let rows: String = payload
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| filter(name))
+        .filter_map(|(name, value)| {
+            scalar(value).map(|value| {
+                format!(
+                    "<tr><th>{}</th><td>{}</td></tr>",
+                    escape(name),
+                    escape(&value)
+                )
+            })
+        })
+        .collect();
+    format!("<table class=\"fields\">{}</table>", rows)
+}
+
+/// The latency matrix of the payload as a table coloured from green (fastest pair) to
+/// red (slowest pair).
+fn latency_section(latency: &Value) -> String {
+    let cpus: Vec<u64> = latency["cpus"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_u64)
+        .collect();
+    let (min, max) = (
+        latency["min_ns"].as_f64().unwrap_or_default(),
+        latency["max_ns"].as_f64().unwrap_or_default(),
+    );
+
+    let mut html = format!("<h2>{}</h2>", escape(&t!("latency")));
+    if cpus.len() > 1 {
+        html.push_str("<table class=\"heatmap\"><tr><th></th>");
+        for cpu in &cpus {
+            html.push_str(&format!("<th>{}</th>", cpu));
+        }
+        html.push_str("</tr>");
+        let rows = latency["matrix_ns"].as_array().into_iter().flatten();
+        for (cpu, row) in cpus.iter().zip(rows) {
+            html.push_str(&format!("<tr><th>{}</th>", cpu));
+            for cell in row.as_array().into_iter().flatten() {
+                match cell.as_f64() {
+                    Some(nanoseconds) => {
+                        // Hue 120 is green and 0 is red
+                        let hue = 120.0 * (1.0 - report::heatmap_position(nanoseconds, min, max));
+                        html.push_str(&format!(
+                            "<td style=\"background:hsl({:.0},75%,55%)\">{:.0}</td>",
+                            hue, nanoseconds
+                        ));
+                    }
+                    None => html.push_str("<td></td>"),
+                }
+            }
+            html.push_str("</tr>");
+        }
+        html.push_str("</table>");
+        html.push_str(&format!(
+            "<p>{}</p>",
+            escape(&t!(
+                "latency-summary",
+                min = format!("{:.1}", min),
+                median = format!("{:.1}", latency["median_ns"].as_f64().unwrap_or_default()),
+                max = format!("{:.1}", max)
+            ))
+        ));
+    } else {
+        html.push_str(&format!("<p>{}</p>", escape(&t!("latency-single-cpu"))));
+    }
+
+    html.push_str(&format!("<h3>{}</h3>", escape(&t!("html-contention"))));
+    html.push_str("<table class=\"fields\">");
+    for contention in latency["contention"].as_array().into_iter().flatten() {
+        // Older payloads have no validity, their rows aren't marked
+        let invalid = if contention["valid"].as_bool() == Some(false) {
+            format!(
+                "<td class=\"invalid\">{}</td>",
+                escape(&t!("html-contention-invalid"))
+            )
+        } else {
+            String::new()
+        };
+        html.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td><td>{:.1} M op/s</td>{}</tr>",
+            escape(contention["primitive"].as_str().unwrap_or_default()),
+            t!(
+                "scaling-threads",
+                threads = contention["threads"].as_u64().unwrap_or_default()
+            ),
+            contention["million_operations_per_second"]
+                .as_f64()
+                .unwrap_or_default(),
+            invalid
+        ));
+    }
+    html.push_str("</table>");
+    html
+}
+
+/// Prints the payload as a standalone HTML page: the scores, the scalar fields that
+/// describe the system and, when measured, the latency heatmap.
+pub fn print_html(payload: &Value) {
+    let title = escape(&t!("html-title"));
+    let mut body = format!("<h1>{}</h1>", title);
+    body.push_str(&format!("<h2>{}</h2>", escape(&t!("html-scores"))));
+    body.push_str(&fields_table(payload, |name| name.starts_with("score_")));
+    body.push_str(&format!("<h2>{}</h2>", escape(&t!("html-system"))));
+    body.push_str(&fields_table(payload, |name| !name.starts_with("score_")));
+    if payload["latency"].is_object() {
+        body.push_str(&latency_section(&payload["latency"]));
+    }
+
+    println!(
+        "<!DOCTYPE html>\n<html lang=\"{}\"><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head><body>{}</body></html>",
+        i18n::language(),
+        title,
+        STYLE,
+        body
+    );
+}
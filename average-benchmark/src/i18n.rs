@@ -0,0 +1,81 @@
+pub use fluent_templates::fluent_bundle::FluentValue;
+use fluent_templates::{static_loader, LanguageIdentifier, Loader};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Message catalogs in `locales/<language>/main.ftl`, embedded at build time. Spanish is
+// the default and the fallback for messages missing in other languages.
+static_loader! {
+    static LOCALES = {
+        locales: "./locales",
+        fallback_language: "es",
+        customise: |bundle| bundle.set_use_isolating(false),
+    };
+}
+
+const SUPPORTED_LANGUAGES: &[&str] = &["es", "en"];
+const DEFAULT_LANGUAGE: &str = "es";
+
+static LANGUAGE: OnceLock<LanguageIdentifier> = OnceLock::new();
+
+/// Parses a POSIX locale such as `en_US.UTF-8` or a language tag such as `en-US` and
+/// keeps it only if there is a catalog for its language.
+fn parse_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let language: LanguageIdentifier = tag.parse().ok()?;
+    SUPPORTED_LANGUAGES
+        .contains(&language.language.as_str())
+        .then(|| language.language.as_str().parse().ok())
+        .flatten()
+}
+
+/// Selects the language of the messages: the `--lang` flag if given, otherwise the first
+/// of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set, otherwise Spanish.
+pub fn init(requested: Option<&str>) {
+    let language = requested
+        .and_then(parse_locale)
+        .or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|name| std::env::var(name).ok())
+                .find(|value| !value.is_empty())
+                .and_then(|value| parse_locale(&value))
+        })
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.parse().expect("valid language tag"));
+    let _ = LANGUAGE.set(language);
+}
+
+pub fn language() -> &'static LanguageIdentifier {
+    LANGUAGE.get_or_init(|| DEFAULT_LANGUAGE.parse().expect("valid language tag"))
+}
+
+pub fn tr(id: &str) -> String {
+    LOCALES.lookup(language(), id)
+}
+
+pub fn tr_args(id: &str, args: Vec<(&'static str, FluentValue<'static>)>) -> String {
+    let args: HashMap<Cow<'static, str>, FluentValue> = args
+        .into_iter()
+        .map(|(name, value)| (Cow::Borrowed(name), value))
+        .collect();
+    LOCALES.lookup_with_args(language(), id, &args)
+}
+
+/// Looks up a message in the catalog of the selected language:
+/// `t!("pending-summary", sent = 2, remaining = 0)`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::tr($id)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::tr_args(
+            $id,
+            vec![$((stringify!($name), $crate::i18n::FluentValue::from($value))),+],
+        )
+    };
+}
+
+pub use crate::t;
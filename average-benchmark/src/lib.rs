@@ -1 +1,18 @@
+//! CPU benchmark used by the `average-benchmark` command line tool.
+//!
+//! [`Benchmark`] runs the workloads and returns a [`BenchmarkReport`] without printing
+//! anything, [`system_info`] describes the machine the results were measured on and
+//! [`ffi`] exposes the benchmark to other languages through a C ABI.
 
+pub mod benchmark;
+pub mod ffi;
+pub mod i18n;
+pub mod profile;
+mod runner;
+pub mod scoring;
+pub mod system_info;
+pub mod telemetry;
+
+pub use benchmark::{MultiThreadResult, ScalingPoint, SingleThreadResult, Workload};
+pub use profile::Profile;
+pub use runner::{Benchmark, BenchmarkReport, Progress};
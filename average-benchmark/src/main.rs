@@ -1,30 +1,240 @@
-mod benchmark;
+mod cli;
+mod config;
+mod consent;
+mod html;
 mod network;
-mod system_info;
+mod outbox;
+mod privacy;
+mod report;
+mod signing;
 mod utils;
 
-use num_cpus;
+use average_benchmark::i18n::{self, t};
+use average_benchmark::{system_info, Benchmark, Progress};
+use clap::Parser;
+use cli::{Cli, Command, RunArgs};
+use config::Config;
+use consent::Consent;
+use log::warn;
+use network::SubmitError;
+use report::OutputFormat;
+use serde_json::Value;
+use std::path::Path;
+use std::process::ExitCode;
+use utils::message;
+
+// Only the binary sets the global allocator, the library leaves it to whoever links it.
+#[cfg(all(feature = "mimalloc", feature = "jemalloc"))]
+compile_error!("the mimalloc and jemalloc features both set the global allocator, enable one");
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    utils::display_banner();
-    utils::display_privacy_policy();
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    i18n::init(cli.lang.as_deref());
+
+    let consent = cli.consent();
+    let result = match config::load_config(&cli.overrides) {
+        Ok(config) => match cli.command.unwrap_or(Command::Run(RunArgs::default())) {
+            Command::Run(args) => run(&config, &args, consent).await,
+            Command::Config => config::display_config(&config),
+            Command::UploadPending => upload_pending(&config).await,
+            Command::Submit { file } => submit_file(&config, &file).await,
+            Command::Keygen => {
+                signing::display_new_keypair();
+                Ok(())
+            }
+        },
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("\x1B[31m{}\x1B[0m", t!("error", error = e.to_string()));
+            match e.downcast_ref::<SubmitError>() {
+                Some(submit_error) => ExitCode::from(submit_error.exit_code()),
+                None => ExitCode::FAILURE,
+            }
+        }
+    }
+}
+
+/// Sends the payload. When the failure is transient the payload is kept in the outbox
+/// so it is not lost, the error is still returned to set the exit code.
+async fn submit(config: &Config, system_info: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let Err(e) = network::send_data(system_info, &config.backend_url, &config.network).await else {
+        return Ok(());
+    };
+
+    if e.is_transient() {
+        let path = outbox::save_pending(system_info)?;
+        message!(
+            "\x1B[33m{}\x1B[0m",
+            t!("saved-to-outbox", path = path.display().to_string())
+        );
+    }
+    Err(e.into())
+}
 
-    let score_single_thread: f64 = benchmark::benchmark_single_thread();
+async fn upload_pending(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let pending = outbox::list_pending()?;
+    if pending.is_empty() {
+        println!("{}", t!("no-pending-submissions"));
+        return Ok(());
+    }
+
+    let mut last_error = None;
+    let mut failed = 0;
+    let mut rejected = 0;
+    for path in &pending {
+        println!(
+            "{}",
+            t!("sending-pending", path = path.display().to_string())
+        );
+        let system_info = outbox::load_pending(path)?;
+        match network::send_data(&system_info, &config.backend_url, &config.network).await {
+            Ok(()) => outbox::remove_pending(path)?,
+            // Sending it again would fail the same way, so it leaves the queue
+            Err(e) if !e.is_transient() => {
+                let rejected_path = outbox::reject_pending(path)?;
+                println!(
+                    "\x1B[31m{}\x1B[0m",
+                    t!(
+                        "pending-rejected",
+                        path = rejected_path.display().to_string(),
+                        error = e.to_string()
+                    )
+                );
+                rejected += 1;
+                last_error = Some(e);
+            }
+            Err(e) => {
+                println!(
+                    "\x1B[31m{}\x1B[0m",
+                    t!(
+                        "pending-send-failed",
+                        path = path.display().to_string(),
+                        error = e.to_string()
+                    )
+                );
+                failed += 1;
+                last_error = Some(e);
+            }
+        }
+    }
 
-    let num_threads: usize = num_cpus::get();
     println!(
-        "\x1B[33mLogical cores numbers detected\x1B[0m: \x1B[36m{}\x1B[0m",
-        num_threads
+        "{}",
+        t!(
+            "pending-summary",
+            sent = pending.len() - failed - rejected,
+            remaining = failed,
+            rejected = rejected
+        )
     );
+    match last_error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+async fn submit_file(config: &Config, file: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let system_info = outbox::read_payload(file)?;
+    submit(config, &system_info).await
+}
+
+/// Runs the benchmark, `consent` is the one given on the command line, if any.
+async fn run(
+    config: &Config,
+    args: &RunArgs,
+    consent: Option<Consent>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let text_output = config.output_format == OutputFormat::Text;
+    utils::set_messages_to_stderr(!text_output);
+
+    if text_output {
+        utils::display_banner();
+        utils::display_privacy_policy();
+    }
+
+    let virtualization = system_info::get_virtualization_info();
+    if text_output {
+        report::print_virtualization(&virtualization);
+        report::print_profile(config.profile, &config.profile.settings());
+    }
+
+    let mut benchmark = Benchmark::new()
+        .profile(config.profile)
+        .threads(virtualization.effective_parallelism);
+    if let Some(workloads) = &config.workloads {
+        benchmark = benchmark.workloads(workloads);
+    }
+    if let Some(storage_dir) = &config.storage_dir {
+        benchmark = benchmark.storage_dir(storage_dir);
+    }
+    if text_output {
+        benchmark = benchmark.on_progress(|progress: Progress| report::print_progress(&progress));
+    }
+    let benchmark_report = benchmark.run();
+    if text_output {
+        if let Some(composite) = benchmark_report.score_composite {
+            report::print_composite(composite);
+        }
+    }
+
+    let mut system_info = system_info::get_system_info(&benchmark_report, &virtualization);
+    privacy::apply_privacy(&mut system_info, &config.privacy);
+
+    if let Some(path) = &args.export_payload {
+        outbox::write_payload(path, &system_info)?;
+        message!(
+            "{}",
+            t!("payload-exported", path = path.display().to_string())
+        );
+        return Ok(());
+    }
+
+    match config.output_format {
+        OutputFormat::Text => utils::display_payload_preview(&system_info),
+        OutputFormat::Json => report::print_json(&system_info),
+        OutputFormat::Html => html::print_html(&system_info),
+    }
+
+    if args.dry_run {
+        return Ok(());
+    }
 
-    let score_multi_thread: f64 = benchmark::benchmark_multi_thread(num_threads);
+    let send = match consent.unwrap_or(config.consent) {
+        Consent::Always => true,
+        Consent::Never => false,
+        Consent::Ask => match consent::ask_to_send() {
+            Some(answer) => {
+                if let (Some(remembered), Some(path)) = (answer.remembered(), &config.path) {
+                    match config::save_consent(path, remembered) {
+                        Ok(()) => {
+                            message!("{}", t!("consent-saved", path = path.display().to_string()))
+                        }
+                        Err(e) => warn!("{}", e),
+                    }
+                }
+                answer.sends()
+            }
+            None => false,
+        },
+    };
 
-    if utils::ask_to_send() {
-        let system_info = system_info::get_system_info(score_single_thread, score_multi_thread);
-        network::send_data(system_info).await?;
+    if send {
+        submit(config, &system_info).await?;
     } else {
-        println!("Datos no enviados.");
+        message!("{}", t!("data-not-sent"));
     }
 
     Ok(())
@@ -1,42 +1,440 @@
-use chrono::DateTime;
-use reqwest::header::HeaderMap;
+use chrono::{DateTime, Utc};
+use log::warn;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
 
-pub async fn send_data(system_info: Value) -> Result<(), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::builder().build()?;
+use crate::signing;
+use crate::utils::message;
+use average_benchmark::i18n::{self, t};
 
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", "application/json".parse()?);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
 
-    let request = client
-        .request(
-            reqwest::Method::POST,
-            concat!(env!("BACKEND_URL"), "/submit-tests"),
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Timeout of every request, in seconds.
+    pub timeout_secs: u64,
+    /// Extra attempts after a network error, a 5xx or a 429.
+    pub max_retries: u32,
+    /// Longest `Retry-After` the client is willing to wait, in seconds. Longer waits
+    /// leave the submission in the outbox instead.
+    pub max_retry_after_secs: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            timeout_secs: 30,
+            max_retries: 3,
+            max_retry_after_secs: 120,
+        }
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn parse_retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after_value(value, Utc::now())
+}
+
+/// `Retry-After` can be a number of seconds or an HTTP date. A date in the past
+/// isn't a wait.
+fn parse_retry_after_value(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - now).to_std().ok()
+}
+
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+/// Why a submission failed. Every kind maps to its own process exit code so wrapper
+/// scripts can react, for example waiting until `reset_at` after a rate limit.
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The API could not be reached: DNS, connection, TLS or timeout errors.
+    Network(reqwest::Error),
+    /// The API returned 429 after the retries.
+    RateLimited {
+        message: String,
+        reset_at: Option<DateTime<Utc>>,
+    },
+    /// The API rejected the payload.
+    ValidationRejected {
+        message: String,
+        field_errors: Vec<(String, String)>,
+    },
+    /// The API (or a proxy in front of it) returned a 5xx after the retries.
+    Server { status: StatusCode, message: String },
+    /// The response isn't the JSON the client expects, e.g. an HTML error page.
+    ProtocolMismatch { status: StatusCode, detail: String },
+    /// The API didn't accept the signature of the submission.
+    SignatureRejected { message: String },
+}
+
+impl SubmitError {
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            SubmitError::Network(_) => 10,
+            SubmitError::RateLimited { .. } => 11,
+            SubmitError::ValidationRejected { .. } => 12,
+            SubmitError::Server { .. } => 13,
+            SubmitError::ProtocolMismatch { .. } => 14,
+            SubmitError::SignatureRejected { .. } => 15,
+        }
+    }
+
+    /// Whether sending the same payload later may succeed, so it is worth keeping it.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            SubmitError::Network(_) | SubmitError::RateLimited { .. } | SubmitError::Server { .. }
         )
-        .headers(headers)
-        .json(&system_info);
+    }
+}
 
-    let response = request.send().await?;
-    let body = response.text().await?;
-    let parsed: Value = serde_json::from_str(&body)?;
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Network(e) => {
+                write!(f, "{}", t!("error-network", error = e.to_string()))
+            }
+            SubmitError::RateLimited { message, reset_at } => match reset_at {
+                Some(reset_at) => write!(
+                    f,
+                    "{}",
+                    t!(
+                        "error-rate-limited-until",
+                        message = message.clone(),
+                        reset = reset_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+                    )
+                ),
+                None => write!(f, "{}", message),
+            },
+            SubmitError::ValidationRejected {
+                message,
+                field_errors,
+            } => {
+                write!(f, "{}", message)?;
+                for (field, error) in field_errors {
+                    write!(f, "\n  - {}: {}", field, error)?;
+                }
+                Ok(())
+            }
+            SubmitError::Server { status, message } => {
+                write!(
+                    f,
+                    "{}",
+                    t!(
+                        "error-server",
+                        status = status.to_string(),
+                        message = message.clone()
+                    )
+                )
+            }
+            SubmitError::ProtocolMismatch { status, detail } => {
+                write!(
+                    f,
+                    "{}",
+                    t!(
+                        "error-protocol",
+                        status = status.to_string(),
+                        detail = detail.clone()
+                    )
+                )
+            }
+            SubmitError::SignatureRejected { message } => {
+                write!(f, "{}", t!("error-signature", message = message.clone()))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SubmitError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SubmitError {
+    fn from(e: reqwest::Error) -> Self {
+        SubmitError::Network(e)
+    }
+}
+
+/// Posts the payload, retrying network errors, 5xx and 429. When the retries are
+/// exhausted the last response is returned so the caller can classify it.
+async fn post_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    system_info: &Value,
+    config: &NetworkConfig,
+) -> Result<Response, reqwest::Error> {
+    let body = serde_json::to_vec(system_info).unwrap_or_default();
+    let signing_key = signing::signing_key();
+
+    let mut attempt = 0;
+    loop {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Ok(language) = HeaderValue::from_str(&i18n::language().to_string()) {
+            headers.insert(ACCEPT_LANGUAGE, language);
+        }
+        if let Some(key) = &signing_key {
+            headers.extend(signing::signature_headers(key, &body));
+        }
+
+        let result = client
+            .request(reqwest::Method::POST, url)
+            .headers(headers)
+            .body(body.clone())
+            .send()
+            .await;
+
+        let (reason, wait) = match &result {
+            Ok(response) if !is_retryable(response.status()) => return result,
+            Ok(response) => (
+                t!("server-responded", status = response.status().to_string()),
+                parse_retry_after(response).unwrap_or_else(|| backoff(attempt)),
+            ),
+            Err(e) => (e.to_string(), backoff(attempt)),
+        };
 
-    let success = parsed["success"].as_bool() == Some(true);
-    let message_type = if success { "Message" } else { "Error" };
-    let color_code = if success { "32" } else { "31" };
-    let content = if success {
-        parsed["message"].as_str().unwrap_or("Success")
+        if attempt >= config.max_retries {
+            return result;
+        }
+        if wait > Duration::from_secs(config.max_retry_after_secs) {
+            warn!(
+                "{}",
+                t!("retry-after-too-long", wait = format!("{:?}", wait))
+            );
+            return result;
+        }
+
+        attempt += 1;
+        message!(
+            "\x1B[33m{}\x1B[0m",
+            t!(
+                "retrying",
+                reason = reason,
+                wait = format!("{:.0?}", wait),
+                attempt = attempt,
+                max = config.max_retries
+            )
+        );
+        tokio::time::sleep(wait).await;
+    }
+}
+
+fn field_errors(parsed: &Value) -> Vec<(String, String)> {
+    parsed["field_errors"]
+        .as_array()
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|error| {
+                    (
+                        error["field"].as_str().unwrap_or("?").to_string(),
+                        error["message"].as_str().unwrap_or("").to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Maps a response that is not a success to its `SubmitError`.
+fn classify_error(
+    status: StatusCode,
+    retry_after: Option<Duration>,
+    parsed: &Value,
+) -> SubmitError {
+    let message = parsed["error"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| t!("unknown-error"));
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let reset_at = parsed["reset_at"]
+            .as_str()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc))
+            .or_else(|| {
+                retry_after
+                    .and_then(|wait| chrono::Duration::from_std(wait).ok())
+                    .map(|wait| Utc::now() + wait)
+            });
+        SubmitError::RateLimited { message, reset_at }
+    } else if status == StatusCode::UNAUTHORIZED {
+        SubmitError::SignatureRejected { message }
+    } else if status.is_server_error() {
+        SubmitError::Server { status, message }
     } else {
-        parsed["error"].as_str().unwrap_or("Unknown error")
+        SubmitError::ValidationRejected {
+            message,
+            field_errors: field_errors(parsed),
+        }
+    }
+}
+
+pub async fn send_data(
+    system_info: &Value,
+    backend_url: &str,
+    config: &NetworkConfig,
+) -> Result<(), SubmitError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .connect_timeout(Duration::from_secs(config.timeout_secs))
+        .build()?;
+
+    let url = format!("{}/submit-tests", backend_url.trim_end_matches('/'));
+    let response = post_with_retries(&client, &url, system_info, config).await?;
+
+    let status = response.status();
+    let retry_after = parse_retry_after(&response);
+    let body = response.text().await?;
+
+    let parsed: Value = match serde_json::from_str(&body) {
+        Ok(parsed @ Value::Object(_)) if parsed["success"].is_boolean() => parsed,
+        _ if status.is_server_error() => {
+            return Err(SubmitError::Server {
+                status,
+                message: body.chars().take(200).collect(),
+            })
+        }
+        _ => {
+            return Err(SubmitError::ProtocolMismatch {
+                status,
+                detail: body.chars().take(200).collect(),
+            })
+        }
     };
 
+    if parsed["success"].as_bool() != Some(true) {
+        return Err(classify_error(status, retry_after, &parsed));
+    }
+
+    let content = parsed["message"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| t!("submit-success"));
     let timestamp = parsed["timestamp"]
         .as_str()
         .and_then(|ts_str| DateTime::parse_from_rfc3339(ts_str).ok())
         .map(|ts| ts.format("%Y-%m-%d %H:%M:%S UTC").to_string())
         .unwrap_or_else(|| "N/A".to_string());
 
-    println!("\x1B[{}m{}: {}\x1B[0m", color_code, message_type, content);
-    println!("\x1B[{}mTimestamp: {}\x1B[0m", color_code, timestamp);
+    message!("\x1B[32m{}\x1B[0m", t!("submit-message", message = content));
+    message!(
+        "\x1B[32m{}\x1B[0m",
+        t!("submit-timestamp", timestamp = timestamp)
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds() {
+        assert_eq!(
+            parse_retry_after_value("120", now()),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(parse_retry_after_value(" 0 ", now()), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_accepts_http_dates() {
+        assert_eq!(
+            parse_retry_after_value("Wed, 21 Oct 2015 07:30:00 GMT", now()),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after_value("Wed, 21 Oct 2015 07:27:00 GMT", now()),
+            None
+        );
+    }
+
+    #[test]
+    fn retry_after_rejects_other_values() {
+        assert_eq!(parse_retry_after_value("", now()), None);
+        assert_eq!(parse_retry_after_value("-5", now()), None);
+        assert_eq!(parse_retry_after_value("1.5", now()), None);
+        assert_eq!(parse_retry_after_value("soon", now()), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(16));
+        assert_eq!(backoff(5), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn only_rate_limits_and_server_errors_are_retried() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn rate_limit_reset_prefers_the_body_over_retry_after() {
+        let parsed = json!({ "error": "slow down", "reset_at": "2015-10-21T08:00:00Z" });
+        let error = classify_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(60)),
+            &parsed,
+        );
+        let SubmitError::RateLimited { reset_at, .. } = error else {
+            panic!("expected a rate limit, got {:?}", error);
+        };
+        assert_eq!(
+            reset_at.map(|ts| ts.to_rfc3339()),
+            Some("2015-10-21T08:00:00+00:00".into())
+        );
+
+        let parsed = json!({ "error": "slow down" });
+        let before = Utc::now();
+        let error = classify_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::from_secs(60)),
+            &parsed,
+        );
+        let SubmitError::RateLimited { reset_at, .. } = error else {
+            panic!("expected a rate limit, got {:?}", error);
+        };
+        let reset_at = reset_at.expect("Retry-After should set reset_at");
+        assert!(reset_at >= before + chrono::Duration::seconds(60));
+        assert!(reset_at <= Utc::now() + chrono::Duration::seconds(60));
+    }
+}
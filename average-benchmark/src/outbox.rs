@@ -0,0 +1,91 @@
+use chrono::Utc;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use average_benchmark::i18n::t;
+
+/// Directory where submissions that could not be sent are kept until
+/// `average-benchmark upload-pending` sends them
+/// (`$XDG_DATA_HOME/average-benchmark/outbox` on Linux).
+pub fn outbox_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("average-benchmark").join("outbox"))
+}
+
+pub fn save_pending(system_info: &Value) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = outbox_dir().ok_or_else(|| t!("no-data-dir"))?;
+    fs::create_dir_all(&dir)?;
+
+    let now = Utc::now();
+    let path = dir.join(format!(
+        "submission-{}-{:09}.json",
+        now.format("%Y%m%dT%H%M%S"),
+        now.timestamp_subsec_nanos()
+    ));
+    write_payload(&path, system_info)?;
+    Ok(path)
+}
+
+/// Pending submissions, oldest first.
+pub fn list_pending() -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let Some(dir) = outbox_dir().filter(|dir| dir.exists()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut pending: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    pending.sort();
+    Ok(pending)
+}
+
+pub fn load_pending(path: &Path) -> Result<Value, Box<dyn std::error::Error>> {
+    read_payload(path)
+}
+
+/// Reads a payload saved in the outbox or exported with `run --export-payload`.
+pub fn read_payload(path: &Path) -> Result<Value, Box<dyn std::error::Error>> {
+    let content = fs::read(path).map_err(|e| {
+        t!(
+            "read-file-error",
+            path = path.display().to_string(),
+            error = e.to_string()
+        )
+    })?;
+    serde_json::from_slice(&content).map_err(|e| {
+        t!(
+            "invalid-payload-file",
+            path = path.display().to_string(),
+            error = e.to_string()
+        )
+        .into()
+    })
+}
+
+pub fn write_payload(path: &Path, system_info: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, serde_json::to_vec_pretty(system_info)?).map_err(|e| {
+        t!(
+            "write-file-error",
+            path = path.display().to_string(),
+            error = e.to_string()
+        )
+        .into()
+    })
+}
+
+pub fn remove_pending(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    Ok(fs::remove_file(path)?)
+}
+
+/// Moves a pending submission the API will never accept to the `rejected` directory of
+/// the outbox, where it is kept for inspection but no longer sent.
+pub fn reject_pending(path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = outbox_dir()
+        .ok_or_else(|| t!("no-data-dir"))?
+        .join("rejected");
+    fs::create_dir_all(&dir)?;
+    let rejected = dir.join(path.file_name().unwrap_or_default());
+    fs::rename(path, &rejected)?;
+    Ok(rejected)
+}
@@ -0,0 +1,139 @@
+use clap::ValueEnum;
+use log::warn;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+
+use average_benchmark::i18n::t;
+
+use crate::config;
+
+// Salt mixed into the machine-id so the pseudonym cannot be matched against the raw
+// machine-id that other applications may expose.
+const MACHINE_ID_SALT: &str = "average-benchmark:machine-id:v1";
+
+// Fields the API needs to publish a result, they can't be opted out.
+const REQUIRED_FIELDS: &[&str] = &[
+    "number_of_cpus",
+    "score_single_thread",
+    "score_multi_thread",
+    "profile",
+    "profile_version",
+];
+
+/// How the machine is identified in the submitted data.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyLevel {
+    /// Sends the host name of the machine.
+    Full,
+    /// Sends a salted hash of the machine-id, stable between runs on the same machine.
+    #[default]
+    Pseudonymous,
+    /// Sends no identifier at all.
+    Anonymous,
+}
+
+impl PrivacyLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrivacyLevel::Full => "full",
+            PrivacyLevel::Pseudonymous => "pseudonymous",
+            PrivacyLevel::Anonymous => "anonymous",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    pub level: PrivacyLevel,
+    /// Payload fields that are never sent, e.g. `["kernel_version", "cpu_flags"]`.
+    pub excluded_fields: Vec<String>,
+}
+
+fn read_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Random identifier of this installation, kept next to the config file and created on
+/// first use. `None` when it can't be stored, a new one on every run wouldn't be stable.
+fn read_install_id() -> Option<String> {
+    let path = config::config_path()?.with_file_name("install-id");
+    if let Some(id) = fs::read_to_string(&path)
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+    {
+        return Some(id);
+    }
+
+    let mut id = [0u8; 16];
+    OsRng.fill_bytes(&mut id);
+    let id = hex::encode(id);
+    fs::create_dir_all(path.parent()?).ok()?;
+    fs::write(&path, &id).ok()?;
+    Some(id)
+}
+
+/// Salted SHA-256 of the machine-id, or of the install id when there is no machine-id,
+/// truncated to 128 bits and hex encoded. The host name isn't used instead, it is often
+/// guessable and its hash could be reversed.
+fn machine_pseudonym() -> Option<String> {
+    let machine_id = read_machine_id().or_else(read_install_id)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(MACHINE_ID_SALT.as_bytes());
+    hasher.update(machine_id.as_bytes());
+    let digest = hasher.finalize();
+
+    Some(
+        digest[..16]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+    )
+}
+
+/// Applies the privacy level and the field opt-out list to the payload built by
+/// `system_info::get_system_info`.
+pub fn apply_privacy(payload: &mut Value, config: &PrivacyConfig) {
+    let Some(fields) = payload.as_object_mut() else {
+        return;
+    };
+
+    match config.level {
+        PrivacyLevel::Full => {}
+        PrivacyLevel::Pseudonymous => match machine_pseudonym() {
+            Some(pseudonym) => {
+                fields.insert("system_host_name".to_string(), Value::String(pseudonym));
+            }
+            None => {
+                fields.remove("system_host_name");
+            }
+        },
+        PrivacyLevel::Anonymous => {
+            fields.remove("system_host_name");
+        }
+    }
+
+    for field in &config.excluded_fields {
+        if REQUIRED_FIELDS.contains(&field.as_str()) {
+            warn!("{}", t!("field-required", field = field.clone()));
+            continue;
+        }
+        fields.remove(field);
+    }
+
+    fields.insert(
+        "privacy_level".to_string(),
+        Value::String(config.level.as_str().to_string()),
+    );
+}
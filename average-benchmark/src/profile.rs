@@ -0,0 +1,97 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::benchmark::Workload;
+
+/// Version of the profile definitions. It changes whenever the settings of a profile
+/// change, only results with the same profile and version are comparable.
+pub const PROFILE_VERSION: u32 = 14;
+
+/// Named run lengths, from a quick sanity check to a run for hardware procurement.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// A sanity check of a few seconds.
+    Quick,
+    /// The full suite, for hardware evaluation.
+    #[default]
+    Standard,
+    /// Longer runs with more repetitions, thread scaling and telemetry.
+    Extended,
+}
+
+/// What a profile runs.
+#[derive(Debug, Clone, Copy)]
+pub struct ProfileSettings {
+    pub workloads: &'static [Workload],
+    /// Iterations of the loop in each workload run.
+    pub iterations: usize,
+    /// Bytes processed by each kernel of the throughput workloads, such as crypto,
+    /// compression and text.
+    pub bytes: usize,
+    /// Operations of the kernels of the integer workload, a fiftieth of them are the
+    /// contended increments of the latency workload and the allocations of each size of
+    /// the allocator workload. Smaller fractions are the tasks, messages and requests of
+    /// the async runtime workload and the syscalls, context switches and processes of
+    /// the OS overhead workload.
+    pub operations: u64,
+    /// Floating point operations of each kernel of the numeric workload, in each of its
+    /// single-thread and multi-thread variants.
+    pub flops: u64,
+    /// Cache line round trips between each pair of CPUs in the latency workload.
+    pub round_trips: u32,
+    /// Runs of each workload, the median one is reported.
+    pub repetitions: u32,
+    /// Whether the multi-thread workload is also run with 1, 2, 4... threads.
+    pub scaling: bool,
+    /// Whether CPU frequency and temperature are sampled after every run.
+    pub telemetry: bool,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Quick => "quick",
+            Profile::Standard => "standard",
+            Profile::Extended => "extended",
+        }
+    }
+
+    pub fn settings(&self) -> ProfileSettings {
+        match self {
+            Profile::Quick => ProfileSettings {
+                workloads: Workload::ALL,
+                iterations: 1_000_000_000,
+                bytes: 64 * 1024 * 1024,
+                operations: 100_000_000,
+                flops: 500_000_000,
+                round_trips: 1_000,
+                repetitions: 1,
+                scaling: false,
+                telemetry: false,
+            },
+            Profile::Standard => ProfileSettings {
+                workloads: Workload::ALL,
+                iterations: 10_000_000_000,
+                bytes: 512 * 1024 * 1024,
+                operations: 500_000_000,
+                flops: 4_000_000_000,
+                round_trips: 10_000,
+                repetitions: 3,
+                scaling: false,
+                telemetry: false,
+            },
+            Profile::Extended => ProfileSettings {
+                workloads: Workload::ALL,
+                iterations: 20_000_000_000,
+                bytes: 1024 * 1024 * 1024,
+                operations: 1_000_000_000,
+                flops: 10_000_000_000,
+                round_trips: 30_000,
+                repetitions: 5,
+                scaling: true,
+                telemetry: true,
+            },
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use average_benchmark::benchmark::allocator::AllocatorResult;
+use average_benchmark::benchmark::async_runtime::AsyncRuntimeResult;
+use average_benchmark::benchmark::compression::CompressionResult;
+use average_benchmark::benchmark::crypto::CryptoResult;
+use average_benchmark::benchmark::integer::IntegerResult;
+use average_benchmark::benchmark::latency::LatencyResult;
+use average_benchmark::benchmark::numeric::NumericResult;
+use average_benchmark::benchmark::os_overhead::OsOverheadResult;
+use average_benchmark::benchmark::storage::StorageResult;
+use average_benchmark::benchmark::text::TextResult;
+use average_benchmark::benchmark::{MultiThreadResult, ScalingPoint, SingleThreadResult};
+use average_benchmark::i18n::t;
+use average_benchmark::profile::{Profile, ProfileSettings, PROFILE_VERSION};
+use average_benchmark::scoring::{REFERENCE_SCORE, SCORING_MODEL_VERSION};
+use average_benchmark::system_info::VirtualizationInfo;
+use average_benchmark::telemetry::TelemetrySample;
+use average_benchmark::Progress;
+
+// 256-colour palette from green to red, for the latency heatmap.
+const HEATMAP_COLORS: &[u8] = &[46, 82, 118, 154, 190, 226, 220, 214, 208, 202, 196];
+
+// Above this many CPUs the heatmap cells are coloured blocks without the value.
+const HEATMAP_MAX_LABELED_CPUS: usize = 16;
+
+/// How the results are printed: coloured text for people, a JSON document for scripts
+/// or an HTML page to share.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    /// A standalone page with the results and the latency heatmap.
+    Html,
+}
+
+pub fn print_virtualization(virtualization: &VirtualizationInfo) {
+    println!(
+        "\x1B[33m{}\x1B[0m: \x1B[36m{}\x1B[0m",
+        t!("logical-cores"),
+        virtualization.logical_cpus
+    );
+    if virtualization.is_constrained() {
+        println!(
+            "\x1B[33m{}\x1B[0m: \x1B[36m{}\x1B[0m",
+            t!("effective-parallelism"),
+            virtualization.effective_parallelism
+        );
+    }
+    if let Some(hypervisor) = &virtualization.hypervisor {
+        println!(
+            "\x1B[33m{}\x1B[0m: \x1B[36m{}\x1B[0m",
+            t!("hypervisor"),
+            hypervisor
+        );
+    }
+    if let Some(runtime) = &virtualization.container_runtime {
+        println!(
+            "\x1B[33m{}\x1B[0m: \x1B[36m{}\x1B[0m",
+            t!("container-runtime"),
+            runtime
+        );
+    }
+}
+
+/// Prints the results of a run as they come.
+pub fn print_progress(progress: &Progress) {
+    match progress {
+        Progress::Repetition {
+            repetitions,
+            telemetry: Some(sample),
+            ..
+        } => print_telemetry_sample(sample, *repetitions),
+        Progress::Repetition { .. } => {}
+        Progress::SingleThread(result) => print_single_thread(result),
+        Progress::MultiThread(result) => print_multi_thread(result),
+        Progress::Crypto(result) => print_crypto(result),
+        Progress::Compression(result) => print_compression(result),
+        Progress::Integer(result) => print_integer(result),
+        Progress::Latency(result) => print_latency(result),
+        Progress::Numeric(result) => print_numeric(result),
+        Progress::Text(result) => print_text(result),
+        Progress::Allocator(result) => print_allocator(result),
+        Progress::AsyncRuntime(result) => print_async_runtime(result),
+        Progress::OsOverhead(result) => print_os_overhead(result),
+        Progress::Storage(result) => print_storage(result),
+        Progress::Scaling(points) => print_scaling(points),
+    }
+}
+
+pub fn print_profile(profile: Profile, settings: &ProfileSettings) {
+    println!(
+        "\x1B[33m{}\x1B[0m: \x1B[36m{}\x1B[0m",
+        t!(
+            "profile",
+            name = profile.as_str(),
+            version = PROFILE_VERSION
+        ),
+        t!(
+            "profile-settings",
+            iterations = settings.iterations,
+            repetitions = settings.repetitions
+        )
+    );
+}
+
+fn print_telemetry_sample(sample: &TelemetrySample, repetitions: u32) {
+    let unknown = "?".to_string();
+    println!(
+        "  {}",
+        t!(
+            "telemetry-sample",
+            repetition = sample.repetition,
+            repetitions = repetitions,
+            duration = sample.duration_ms.to_string(),
+            frequency = sample
+                .cpu_frequency_mhz
+                .map(|mhz| mhz.to_string())
+                .unwrap_or_else(|| unknown.clone()),
+            temperature = sample
+                .temperature_c
+                .map(|celsius| format!("{:.1}", celsius))
+                .unwrap_or(unknown)
+        )
+    );
+}
+
+fn print_scaling(points: &[ScalingPoint]) {
+    println!("\x1B[33m{}\x1B[0m", t!("scaling"));
+    for point in points {
+        println!(
+            "  {}: {:.3}",
+            t!("scaling-threads", threads = point.threads),
+            point.score
+        );
+    }
+}
+
+fn print_single_thread(result: &SingleThreadResult) {
+    println!(
+        "\x1B[33m{}\x1B[0m: \x1B[36m{:.3}s\x1B[0m",
+        t!("single-thread-time"),
+        result.duration.as_secs_f64()
+    );
+    println!(
+        "\x1B[33m{}\x1B[0m: {:.3e}",
+        t!("single-thread-result"),
+        result.result
+    );
+    println!(
+        "\x1B[32m{}\x1B[0m: {:.3}",
+        t!("single-thread-score"),
+        result.score
+    );
+}
+
+fn print_multi_thread(result: &MultiThreadResult) {
+    println!(
+        "\x1B[33m{}\x1B[0m: \x1B[36m{:.2?}\x1B[0m",
+        t!("multi-thread-total-time"),
+        result.duration
+    );
+    println!(
+        "\x1B[33m{}\x1B[0m: {:.3e}",
+        t!("multi-thread-result"),
+        result.result
+    );
+    println!(
+        "\x1B[32m{}\x1B[0m: {:.3}",
+        t!("multi-thread-score"),
+        result.score
+    );
+
+    println!(
+        "\x1B[33m{}\x1B[0m: {:.2?}",
+        t!("average-iterations-per-thread"),
+        result.iterations / result.threads.max(1)
+    );
+    println!(
+        "\x1B[33m{}\x1B[0m: {:.2?} (\x1B[32m{}\x1B[0m)",
+        t!("min-thread-time"),
+        result.min_time,
+        t!("thread", thread = result.min_thread)
+    );
+    println!(
+        "\x1B[33m{}\x1B[0m: {:.2?} (\x1B[32m{}\x1B[0m)",
+        t!("max-thread-time"),
+        result.max_time,
+        t!("thread", thread = result.max_thread)
+    );
+}
+
+fn print_crypto(result: &CryptoResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("crypto"));
+    for algorithm in &result.algorithms {
+        println!(
+            "  {}",
+            t!(
+                "crypto-throughput",
+                algorithm = algorithm.algorithm.name(),
+                throughput = format!("{:.1}", algorithm.megabytes_per_second),
+                features = algorithm
+                    .cpu_features
+                    .map(str::to_string)
+                    .unwrap_or_else(|| t!("crypto-no-cpu-features"))
+            )
+        );
+        let comparison = match (
+            algorithm.software_megabytes_per_second,
+            algorithm.accelerated,
+        ) {
+            (Some(software), Some(accelerated)) => t!(
+                if accelerated {
+                    "crypto-accelerated"
+                } else {
+                    "crypto-not-accelerated"
+                },
+                software = format!("{software:.1}"),
+                speedup = format!("{:.1}", algorithm.megabytes_per_second / software)
+            ),
+            _ => t!("crypto-no-software"),
+        };
+        println!("    {comparison}");
+    }
+    print_workload_score(t!("crypto-score"), result.score);
+}
+
+fn print_compression(result: &CompressionResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("compression"));
+    for codec in result.single_thread.iter().chain(&result.multi_thread) {
+        println!(
+            "  {}",
+            t!(
+                "compression-throughput",
+                codec = codec.codec.name(),
+                threads = t!("scaling-threads", threads = codec.threads),
+                compress = format!("{:.1}", codec.compress_megabytes_per_second),
+                decompress = format!("{:.1}", codec.decompress_megabytes_per_second),
+                ratio = format!("{:.2}", codec.ratio)
+            )
+        );
+        print_validity(codec.valid);
+    }
+    print_workload_score(t!("compression-score"), result.score);
+}
+
+fn print_integer(result: &IntegerResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("integer"));
+    for kernel in &result.kernels {
+        println!(
+            "  {}",
+            t!(
+                "integer-throughput",
+                kernel = kernel.kernel.name(),
+                throughput = format!("{:.1}", kernel.million_operations_per_second)
+            )
+        );
+    }
+    print_workload_score(t!("integer-score"), result.score);
+}
+
+fn print_numeric(result: &NumericResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("numeric"));
+    for kernel in result.single_thread.iter().chain(&result.multi_thread) {
+        println!(
+            "  {}",
+            t!(
+                "numeric-throughput",
+                kernel = kernel.kernel.name(),
+                threads = t!("scaling-threads", threads = kernel.threads),
+                throughput = format!("{:.2}", kernel.throughput),
+                unit = kernel.kernel.unit(),
+                error = format!("{:.1e}", kernel.error)
+            )
+        );
+        print_validity(kernel.valid);
+    }
+    print_workload_score(t!("numeric-score"), result.score);
+}
+
+fn print_text(result: &TextResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("text"));
+    for kernel in &result.kernels {
+        println!(
+            "  {}",
+            t!(
+                "text-throughput",
+                kernel = kernel.kernel.name(),
+                throughput = format!("{:.1}", kernel.megabytes_per_second)
+            )
+        );
+        print_validity(kernel.valid);
+    }
+    print_workload_score(t!("text-score"), result.score);
+}
+
+fn print_allocator(result: &AllocatorResult) {
+    println!(
+        "\x1B[33m{}\x1B[0m",
+        t!("allocator", allocator = result.allocator)
+    );
+    for size in result.single_thread.iter().chain(&result.multi_thread) {
+        println!(
+            "  {}",
+            t!(
+                "allocator-throughput",
+                size = size.size.name(),
+                threads = t!("scaling-threads", threads = size.threads),
+                throughput = format!("{:.2}", size.million_operations_per_second)
+            )
+        );
+    }
+    if let Some(page_faults) = &result.page_faults {
+        println!(
+            "  {}",
+            t!(
+                "page-faults",
+                nanoseconds = format!("{:.0}", page_faults.nanoseconds_per_fault),
+                throughput = format!("{:.2}", page_faults.gigabytes_per_second)
+            )
+        );
+        match &page_faults.huge_pages {
+            Some(huge_pages) => println!(
+                "  {}",
+                t!(
+                    "page-faults-huge",
+                    throughput = format!("{:.2}", huge_pages.gigabytes_per_second),
+                    speedup = format!("{:.1}", huge_pages.speedup),
+                    huge = huge_pages.huge_page_bytes / (1024 * 1024)
+                )
+            ),
+            None => println!("  {}", t!("page-faults-no-huge")),
+        }
+    }
+    print_workload_score(t!("allocator-score"), result.score);
+}
+
+fn print_async_runtime(result: &AsyncRuntimeResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("async-runtime"));
+    for point in &result.points {
+        println!("  {}", t!("async-workers", workers = point.workers));
+        for kernel in &point.kernels {
+            println!(
+                "    {}",
+                t!(
+                    "async-throughput",
+                    kernel = kernel.kernel.name(),
+                    throughput = format!("{:.0}", kernel.operations_per_second),
+                    unit = kernel.kernel.unit()
+                )
+            );
+        }
+        for error in &point.errors {
+            println!(
+                "    \x1B[31m{}\x1B[0m",
+                t!(
+                    "async-kernel-failed",
+                    kernel = error.kernel.name(),
+                    error = error.error.clone()
+                )
+            );
+        }
+    }
+    match result.score {
+        Some(score) => println!("\x1B[32m{}\x1B[0m: {:.3}", t!("async-score"), score),
+        None => println!(
+            "\x1B[32m{}\x1B[0m: {}",
+            t!("async-score"),
+            t!("async-incomplete")
+        ),
+    }
+}
+
+fn print_os_overhead(result: &OsOverheadResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("os-overhead"));
+    if result.kernels.is_empty() {
+        println!("  {}", t!("os-overhead-unsupported"));
+    }
+    for kernel in &result.kernels {
+        println!(
+            "  {}",
+            t!(
+                "os-overhead-latency",
+                kernel = kernel.kernel.name(),
+                nanoseconds = format!("{:.0}", kernel.nanoseconds)
+            )
+        );
+    }
+}
+
+fn print_storage(result: &StorageResult) {
+    let unknown = "?".to_string();
+    println!(
+        "\x1B[33m{}\x1B[0m",
+        t!(
+            "storage",
+            filesystem = result.device.filesystem.clone().unwrap_or(unknown.clone()),
+            device = result.device.block_device.clone().unwrap_or(unknown)
+        )
+    );
+    let Some(measurements) = &result.measurements else {
+        println!(
+            "  {}",
+            t!(
+                "storage-error",
+                error = result.error.clone().unwrap_or_default()
+            )
+        );
+        return;
+    };
+    println!(
+        "  {}",
+        t!(
+            "storage-sequential",
+            write = format!("{:.0}", measurements.sequential_write_megabytes_per_second),
+            read = format!("{:.0}", measurements.sequential_read_megabytes_per_second)
+        )
+    );
+    println!(
+        "  {}",
+        t!(
+            "storage-random",
+            read = format!("{:.0}", measurements.random_read_iops),
+            write = measurements
+                .random_write_iops
+                .map(|iops| format!("{:.0}", iops))
+                .unwrap_or_else(|| "-".to_string())
+        )
+    );
+    if !measurements.direct_io {
+        println!("  {}", t!("storage-no-direct-io"));
+    }
+    println!(
+        "  {}",
+        t!(
+            "storage-fsync",
+            median = format!("{:.0}", measurements.fsync.median_microseconds),
+            p99 = format!("{:.0}", measurements.fsync.p99_microseconds)
+        )
+    );
+}
+
+/// Position of `value` between `min` and `max`, from 0 to 1.
+pub fn heatmap_position(value: f64, min: f64, max: f64) -> f64 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+fn print_latency(result: &LatencyResult) {
+    println!("\x1B[33m{}\x1B[0m", t!("latency"));
+    let (Some(min), Some(median), Some(max)) = (result.min_ns, result.median_ns, result.max_ns)
+    else {
+        println!("  {}", t!("latency-single-cpu"));
+        print_contention(result);
+        return;
+    };
+    if !result.pinned {
+        println!("  {}", t!("latency-not-pinned"));
+    }
+
+    let labeled = result.cpus.len() <= HEATMAP_MAX_LABELED_CPUS;
+    let width = if labeled { 6 } else { 2 };
+    // Columns are in the same order as the rows, only labeled when they fit
+    if labeled {
+        let header: String = result
+            .cpus
+            .iter()
+            .map(|cpu| format!("{:>width$}", cpu, width = width))
+            .collect();
+        println!("  {:>5}{}", "", header);
+    }
+    for (cpu, row) in result.cpus.iter().zip(&result.matrix_ns) {
+        let cells: String = row
+            .iter()
+            .map(|latency| match latency {
+                Some(latency) => {
+                    let position = heatmap_position(*latency, min, max);
+                    let color = HEATMAP_COLORS
+                        [(position * (HEATMAP_COLORS.len() - 1) as f64).round() as usize];
+                    let label = if labeled {
+                        format!("{:>width$.0}", latency, width = width)
+                    } else {
+                        " ".repeat(width)
+                    };
+                    format!("\x1B[30;48;5;{}m{}\x1B[0m", color, label)
+                }
+                None => " ".repeat(width),
+            })
+            .collect();
+        println!("  {:>5}{}", cpu, cells);
+    }
+    println!(
+        "  {}",
+        t!(
+            "latency-summary",
+            min = format!("{:.1}", min),
+            median = format!("{:.1}", median),
+            max = format!("{:.1}", max)
+        )
+    );
+    print_contention(result);
+}
+
+fn print_contention(result: &LatencyResult) {
+    for contention in &result.contention {
+        println!(
+            "  {}",
+            t!(
+                "contention-throughput",
+                primitive = contention.primitive.name(),
+                threads = t!("scaling-threads", threads = contention.threads),
+                throughput = format!("{:.1}", contention.million_operations_per_second)
+            )
+        );
+        print_validity(contention.valid);
+    }
+}
+
+/// Prints the score of a workload, or that it has none because no kernel gave a
+/// valid result.
+fn print_workload_score(label: String, score: Option<f64>) {
+    match score {
+        Some(score) => println!("\x1B[32m{}\x1B[0m: {:.3}", label, score),
+        None => println!("\x1B[32m{}\x1B[0m: {}", label, t!("workload-unscored")),
+    }
+}
+
+/// Warns under a kernel whose result was wrong, so its speed doesn't mean anything.
+fn print_validity(valid: bool) {
+    if !valid {
+        println!("    \x1B[31m{}\x1B[0m", t!("result-invalid"));
+    }
+}
+
+pub fn print_composite(score: f64) {
+    println!(
+        "\x1B[32m{}\x1B[0m: {:.3}",
+        t!(
+            "composite-score",
+            version = SCORING_MODEL_VERSION,
+            reference = REFERENCE_SCORE
+        ),
+        score
+    );
+}
+
+pub fn print_json(payload: &Value) {
+    match serde_json::to_string_pretty(payload) {
+        Ok(json) => println!("{}", json),
+        Err(_) => println!("{}", payload),
+    }
+}
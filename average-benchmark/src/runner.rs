@@ -0,0 +1,362 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::benchmark::allocator::{self, AllocatorResult};
+use crate::benchmark::async_runtime::{self, AsyncRuntimeResult};
+use crate::benchmark::compression::{self, CompressionResult};
+use crate::benchmark::crypto::{self, CryptoResult};
+use crate::benchmark::integer::{self, IntegerResult};
+use crate::benchmark::latency::{self, LatencyResult};
+use crate::benchmark::numeric::{self, NumericResult};
+use crate::benchmark::os_overhead::{self, OsOverheadResult};
+use crate::benchmark::storage::{self, StorageResult};
+use crate::benchmark::text::{self, TextResult};
+use crate::benchmark::{self, MultiThreadResult, ScalingPoint, SingleThreadResult, Workload};
+use crate::profile::{Profile, PROFILE_VERSION};
+use crate::scoring::{self, SCORING_MODEL_VERSION};
+use crate::system_info;
+use crate::telemetry::{Telemetry, TelemetrySample};
+
+/// Something that happened during a run, passed to the `on_progress` callback so
+/// callers can show it as it happens.
+#[derive(Debug)]
+pub enum Progress<'a> {
+    /// One repetition of a workload finished. `telemetry` is the sample taken right
+    /// after it, when the profile collects telemetry.
+    Repetition {
+        workload: Workload,
+        repetition: u32,
+        repetitions: u32,
+        duration: Duration,
+        telemetry: Option<&'a TelemetrySample>,
+    },
+    SingleThread(&'a SingleThreadResult),
+    MultiThread(&'a MultiThreadResult),
+    Crypto(&'a CryptoResult),
+    Compression(&'a CompressionResult),
+    Integer(&'a IntegerResult),
+    Latency(&'a LatencyResult),
+    Numeric(&'a NumericResult),
+    Text(&'a TextResult),
+    Allocator(&'a AllocatorResult),
+    AsyncRuntime(&'a AsyncRuntimeResult),
+    OsOverhead(&'a OsOverheadResult),
+    Storage(&'a StorageResult),
+    Scaling(&'a [ScalingPoint]),
+}
+
+type ProgressCallback<'a> = Box<dyn FnMut(Progress) + 'a>;
+
+/// Everything a run measured. Results of different profile or scoring model versions
+/// are not comparable.
+#[derive(Debug, Serialize)]
+pub struct BenchmarkReport {
+    pub profile: Profile,
+    pub profile_version: u32,
+    pub scoring_model_version: u32,
+    /// Threads used by the multi-thread workloads.
+    pub threads: usize,
+    pub single_thread: Option<SingleThreadResult>,
+    pub multi_thread: Option<MultiThreadResult>,
+    pub crypto: Option<CryptoResult>,
+    pub compression: Option<CompressionResult>,
+    pub integer: Option<IntegerResult>,
+    pub latency: Option<LatencyResult>,
+    pub numeric: Option<NumericResult>,
+    pub text: Option<TextResult>,
+    pub allocator: Option<AllocatorResult>,
+    pub async_runtime: Option<AsyncRuntimeResult>,
+    pub os_overhead: Option<OsOverheadResult>,
+    pub storage: Option<StorageResult>,
+    pub scaling: Vec<ScalingPoint>,
+    pub telemetry: Vec<TelemetrySample>,
+    /// Geometric mean of the workload scores.
+    pub score_composite: Option<f64>,
+}
+
+/// Runs the benchmark suite without printing anything:
+///
+/// ```no_run
+/// use average_benchmark::{Benchmark, Workload};
+///
+/// let report = Benchmark::new()
+///     .workloads(&[Workload::SingleThread])
+///     .threads(4)
+///     .run();
+/// println!("{:?}", report.score_composite);
+/// ```
+pub struct Benchmark<'a> {
+    profile: Profile,
+    workloads: Option<Vec<Workload>>,
+    threads: Option<usize>,
+    storage_dir: Option<PathBuf>,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl Default for Benchmark<'_> {
+    fn default() -> Self {
+        Benchmark::new()
+    }
+}
+
+impl<'a> Benchmark<'a> {
+    /// The standard profile with its workloads, on every CPU available to the process.
+    pub fn new() -> Self {
+        Benchmark {
+            profile: Profile::default(),
+            workloads: None,
+            threads: None,
+            storage_dir: None,
+            on_progress: None,
+        }
+    }
+
+    /// Profile that sets the workloads, sizes, repetitions and telemetry.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Workloads to run instead of the ones of the profile.
+    pub fn workloads(mut self, workloads: &[Workload]) -> Self {
+        self.workloads = Some(workloads.to_vec());
+        self
+    }
+
+    /// Threads of the multi-thread workloads. Defaults to the effective parallelism,
+    /// which takes cgroup and cpuset limits into account.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// Directory of the scratch file of the storage workload, the temporary directory
+    /// by default. Setting it also adds the storage workload, which no profile runs.
+    pub fn storage_dir(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.storage_dir = Some(directory.into());
+        self
+    }
+
+    /// Called as the run progresses, e.g. to print each result as soon as it is ready.
+    pub fn on_progress(mut self, on_progress: impl FnMut(Progress) + 'a) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn run(mut self) -> BenchmarkReport {
+        let settings = self.profile.settings();
+        let mut workloads = self
+            .workloads
+            .take()
+            .unwrap_or_else(|| settings.workloads.to_vec());
+        if self.storage_dir.is_some() && !workloads.contains(&Workload::Storage) {
+            workloads.push(Workload::Storage);
+        }
+        let threads = self
+            .threads
+            .unwrap_or_else(|| system_info::get_virtualization_info().effective_parallelism);
+        let mut runner = WorkloadRunner {
+            workloads,
+            repetitions: settings.repetitions,
+            telemetry: settings.telemetry.then(Telemetry::new),
+            on_progress: self.on_progress.take(),
+        };
+
+        let single_thread = runner.run_workload(
+            Workload::SingleThread,
+            || benchmark::benchmark_single_thread(settings.iterations),
+            |result| result.duration,
+            |result| Progress::SingleThread(result),
+        );
+        let multi_thread = runner.run_workload(
+            Workload::MultiThread,
+            || benchmark::benchmark_multi_thread(threads, settings.iterations),
+            |result| result.duration,
+            |result| Progress::MultiThread(result),
+        );
+        let crypto = runner.run_workload(
+            Workload::Crypto,
+            || crypto::benchmark_crypto(settings.bytes),
+            |result| result.duration,
+            |result| Progress::Crypto(result),
+        );
+        let compression = runner.run_workload(
+            Workload::Compression,
+            || compression::benchmark_compression(settings.bytes, threads),
+            |result| result.duration,
+            |result| Progress::Compression(result),
+        );
+        let integer = runner.run_workload(
+            Workload::Integer,
+            || integer::benchmark_integer(settings.operations),
+            |result| result.duration,
+            |result| Progress::Integer(result),
+        );
+        // Measured once, every pair of CPUs is already measured several times
+        let latency = runner.run_once(
+            Workload::Latency,
+            || latency::benchmark_latency(settings.round_trips, threads, settings.operations / 50),
+            |result| Progress::Latency(result),
+        );
+        let numeric = runner.run_workload(
+            Workload::Numeric,
+            || numeric::benchmark_numeric(settings.flops, threads),
+            |result| result.duration,
+            |result| Progress::Numeric(result),
+        );
+        let text = runner.run_workload(
+            Workload::Text,
+            || text::benchmark_text(settings.bytes),
+            |result| result.duration,
+            |result| Progress::Text(result),
+        );
+        let allocator = runner.run_workload(
+            Workload::Allocator,
+            || allocator::benchmark_allocator(settings.operations, threads),
+            |result| result.duration,
+            |result| Progress::Allocator(result),
+        );
+        let async_runtime = runner.run_workload(
+            Workload::AsyncRuntime,
+            || async_runtime::benchmark_async_runtime(settings.operations, threads),
+            |result| result.duration,
+            |result| Progress::AsyncRuntime(result),
+        );
+        let os_overhead = runner.run_workload(
+            Workload::OsOverhead,
+            || os_overhead::benchmark_os_overhead(settings.operations),
+            |result| result.duration,
+            |result| Progress::OsOverhead(result),
+        );
+        // Measured once, each run writes the whole file to the disk
+        let storage_dir = self.storage_dir.clone().unwrap_or_else(std::env::temp_dir);
+        let storage = runner.run_once(
+            Workload::Storage,
+            || storage::benchmark_storage(&storage_dir, settings.bytes),
+            |result| Progress::Storage(result),
+        );
+
+        let scaling: Vec<ScalingPoint> = if settings.scaling {
+            benchmark::scaling_thread_counts(threads)
+                .into_iter()
+                .map(|threads| ScalingPoint {
+                    threads,
+                    score: benchmark::benchmark_multi_thread(threads, settings.iterations).score,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if !scaling.is_empty() {
+            runner.progress(Progress::Scaling(&scaling));
+        }
+
+        let scores: Vec<f64> = single_thread
+            .iter()
+            .map(|result| result.score)
+            .chain(multi_thread.iter().map(|result| result.score))
+            .chain(crypto.iter().filter_map(|result| result.score))
+            .chain(compression.iter().filter_map(|result| result.score))
+            .chain(integer.iter().filter_map(|result| result.score))
+            .chain(numeric.iter().filter_map(|result| result.score))
+            .chain(text.iter().filter_map(|result| result.score))
+            .chain(allocator.iter().filter_map(|result| result.score))
+            .chain(async_runtime.iter().filter_map(|result| result.score))
+            .collect();
+
+        BenchmarkReport {
+            profile: self.profile,
+            profile_version: PROFILE_VERSION,
+            scoring_model_version: SCORING_MODEL_VERSION,
+            threads,
+            single_thread,
+            multi_thread,
+            crypto,
+            compression,
+            integer,
+            latency,
+            numeric,
+            text,
+            allocator,
+            async_runtime,
+            os_overhead,
+            storage,
+            scaling,
+            telemetry: runner
+                .telemetry
+                .map(|telemetry| telemetry.samples)
+                .unwrap_or_default(),
+            score_composite: scoring::composite_score(&scores),
+        }
+    }
+}
+
+/// Runs the selected workloads of a run and reports their progress.
+struct WorkloadRunner<'a> {
+    workloads: Vec<Workload>,
+    repetitions: u32,
+    telemetry: Option<Telemetry>,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl WorkloadRunner<'_> {
+    fn progress(&mut self, progress: Progress) {
+        if let Some(on_progress) = self.on_progress.as_mut() {
+            on_progress(progress);
+        }
+    }
+
+    /// Reports a finished repetition of `workload` with its telemetry sample.
+    fn repetition(&mut self, workload: Workload, repetition: u32, duration: Duration) {
+        let sample = self
+            .telemetry
+            .as_mut()
+            .map(|telemetry| telemetry.sample(workload, repetition, duration));
+        if let Some(on_progress) = self.on_progress.as_mut() {
+            on_progress(Progress::Repetition {
+                workload,
+                repetition,
+                repetitions: self.repetitions,
+                duration,
+                telemetry: sample,
+            });
+        }
+    }
+
+    /// Runs `workload` if it is selected, `repetitions` times keeping the median run.
+    /// Each repetition is reported with its telemetry sample, then the kept result.
+    fn run_workload<R>(
+        &mut self,
+        workload: Workload,
+        run: impl FnMut() -> R,
+        duration: fn(&R) -> Duration,
+        progress: fn(&R) -> Progress,
+    ) -> Option<R> {
+        if !self.workloads.contains(&workload) {
+            return None;
+        }
+        let result =
+            benchmark::median_run(self.repetitions, run, duration, |repetition, result| {
+                self.repetition(workload, repetition, duration(result))
+            });
+        self.progress(progress(&result));
+        Some(result)
+    }
+
+    /// Runs `workload` once if it is selected, for the workloads that repeat their
+    /// measurements themselves or are too slow to repeat.
+    fn run_once<R>(
+        &mut self,
+        workload: Workload,
+        run: impl FnOnce() -> R,
+        progress: fn(&R) -> Progress,
+    ) -> Option<R> {
+        if !self.workloads.contains(&workload) {
+            return None;
+        }
+        let result = run();
+        self.progress(progress(&result));
+        Some(result)
+    }
+}
@@ -0,0 +1,260 @@
+use std::time::Duration;
+
+use crate::benchmark::allocator::AllocationSize;
+use crate::benchmark::async_runtime::AsyncKernel;
+use crate::benchmark::compression::CompressionCodec;
+use crate::benchmark::crypto::CryptoAlgorithm;
+use crate::benchmark::integer::IntegerKernel;
+use crate::benchmark::numeric::NumericKernel;
+use crate::benchmark::text::TextKernel;
+use crate::benchmark::Workload;
+
+/// Version of the scoring model. It changes whenever the reference values or the way
+/// scores are combined change, scores of different versions are not comparable.
+pub const SCORING_MODEL_VERSION: u32 = 10;
+
+/// Score of the reference machine in every workload.
+pub const REFERENCE_SCORE: f64 = 1000.0;
+
+/// Throughput of the reference machine in `workload`, in iterations per second. `None`
+/// for workloads scored by the geometric mean of several kernels, such as the crypto
+/// workload, whose references are given per kernel.
+///
+/// The reference machine of model v10 is a KVM guest with one vCPU of an Intel Xeon
+/// Processor @ 2.10GHz (family 6, model 207, Raptor Cove cores). Every reference is the
+/// mean of two runs of the standard profile with the release build and the system
+/// allocator. It has a single core, so every reference is a one-core throughput and
+/// multi-thread scores are relative to one core: a machine whose threads scale
+/// perfectly scores about its number of cores times its single-thread score.
+pub fn reference_throughput(workload: Workload) -> Option<f64> {
+    match workload {
+        Workload::SingleThread => Some(1.8e9),
+        Workload::MultiThread => Some(1.8e9),
+        Workload::Crypto
+        | Workload::Compression
+        | Workload::Integer
+        | Workload::Numeric
+        | Workload::Text
+        | Workload::Allocator
+        | Workload::AsyncRuntime => None,
+        // Describes the topology rather than the speed of the machine
+        Workload::Latency => None,
+        // Depends on the kernel, its mitigations and the hypervisor more than on the CPU
+        Workload::OsOverhead => None,
+        // Measures the disk rather than the CPU
+        Workload::Storage => None,
+    }
+}
+
+/// Throughput of the reference machine in each algorithm of the crypto workload, in
+/// MB/s. Its CPU has SHA-NI, AVX-512, VAES and AVX2, see
+/// `CryptoAlgorithm::detected_cpu_features`.
+pub fn reference_crypto_throughput(algorithm: CryptoAlgorithm) -> f64 {
+    match algorithm {
+        CryptoAlgorithm::Sha256 => 1300.0,
+        CryptoAlgorithm::Blake3 => 5100.0,
+        CryptoAlgorithm::Aes256Gcm => 7000.0,
+        CryptoAlgorithm::ChaCha20Poly1305 => 1600.0,
+    }
+}
+
+/// Compression and decompression throughput of the reference machine for each codec,
+/// in MB/s of uncompressed data: LZ4 from lz4_flex, zstd at level 3 and deflate at
+/// level 6 from miniz_oxide. Measured on one thread, the multi-thread variant is
+/// compared with it too.
+pub fn reference_compression_throughput(codec: CompressionCodec) -> (f64, f64) {
+    match codec {
+        CompressionCodec::Lz4 => (340.0, 840.0),
+        CompressionCodec::Zstd => (190.0, 590.0),
+        CompressionCodec::Deflate => (16.0, 270.0),
+    }
+}
+
+/// Throughput of the reference machine in each kernel of the integer workload, in
+/// millions of instructions, operations or sorted elements per second.
+pub fn reference_integer_throughput(kernel: IntegerKernel) -> f64 {
+    match kernel {
+        IntegerKernel::Interpreter => 350.0,
+        IntegerKernel::HashMap => 17.0,
+        IntegerKernel::BTreeMap => 4.1,
+        IntegerKernel::SortRandom => 32.0,
+        IntegerKernel::SortNearlySorted => 42.0,
+    }
+}
+
+/// Throughput of the reference machine in each kernel of the numeric workload, in
+/// GFLOP/s for the n-body and FFT kernels and GB/s for the SpMV and stencil ones.
+/// Measured on one thread, the multi-thread variant is compared with it too.
+pub fn reference_numeric_throughput(kernel: NumericKernel) -> f64 {
+    match kernel {
+        NumericKernel::NBody => 4.9,
+        NumericKernel::Fft => 3.8,
+        NumericKernel::SpMV => 17.0,
+        NumericKernel::Stencil => 21.0,
+    }
+}
+
+/// Throughput of the reference machine in each kernel of the text workload, in MB/s of
+/// input. The regex kernel searches each megabyte with every pattern.
+pub fn reference_text_throughput(kernel: TextKernel) -> f64 {
+    match kernel {
+        TextKernel::JsonParse => 310.0,
+        TextKernel::Utf8Validate => 1000.0,
+        TextKernel::Utf8Transcode => 170.0,
+        TextKernel::CsvTokenize => 570.0,
+        TextKernel::Regex => 460.0,
+    }
+}
+
+/// Allocation and free pairs per second of the reference machine with the system
+/// allocator (glibc), in millions. Measured on one thread, the multi-thread variant is
+/// compared with it too.
+pub fn reference_allocation_throughput(size: AllocationSize) -> f64 {
+    match size {
+        AllocationSize::Small => 31.0,
+        AllocationSize::Mixed => 5.0,
+        AllocationSize::Large => 1.4,
+    }
+}
+
+/// Operations per second of each kernel of the async runtime workload on a tokio
+/// runtime with one worker, the reference machine's only core. The runs with more
+/// workers are compared with it too.
+pub fn reference_async_throughput(kernel: AsyncKernel) -> f64 {
+    match kernel {
+        AsyncKernel::Spawn => 1_900_000.0,
+        AsyncKernel::Channel => 8_500_000.0,
+        AsyncKernel::TcpEcho => 120_000.0,
+    }
+}
+
+/// Throughput relative to `reference`, which scores `REFERENCE_SCORE`.
+pub fn throughput_score(throughput: f64, reference: f64) -> f64 {
+    if throughput > 0.0 && reference > 0.0 {
+        throughput / reference * REFERENCE_SCORE
+    } else {
+        0.0
+    }
+}
+
+/// Score of a workload run: its throughput relative to the reference machine, which
+/// scores `REFERENCE_SCORE`.
+pub fn workload_score(workload: Workload, iterations: usize, duration: Duration) -> f64 {
+    let seconds = duration.as_secs_f64();
+    match reference_throughput(workload) {
+        Some(reference) if seconds > 0.0 => {
+            throughput_score(iterations as f64 / seconds, reference)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Megabytes (10⁶ bytes) per second.
+pub fn megabytes_per_second(bytes: usize, duration: Duration) -> f64 {
+    let seconds = duration.as_secs_f64();
+    if seconds > 0.0 {
+        bytes as f64 / 1e6 / seconds
+    } else {
+        0.0
+    }
+}
+
+/// Geometric mean of the workload scores, so a workload that doubles moves the
+/// composite by the same factor whatever its scale. `None` without scores.
+pub fn composite_score(scores: &[f64]) -> Option<f64> {
+    if scores.is_empty()
+        || scores
+            .iter()
+            .any(|score| !score.is_finite() || *score <= 0.0)
+    {
+        return None;
+    }
+    let log_sum: f64 = scores.iter().map(|score| score.ln()).sum();
+    Some((log_sum / scores.len() as f64).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-12,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn reference_throughput_scores_the_reference_score() {
+        assert_close(throughput_score(5100.0, 5100.0), REFERENCE_SCORE);
+        assert_close(throughput_score(10200.0, 5100.0), 2.0 * REFERENCE_SCORE);
+        assert_close(throughput_score(1.0, 4.0), REFERENCE_SCORE / 4.0);
+    }
+
+    #[test]
+    fn missing_throughput_scores_zero() {
+        assert_eq!(throughput_score(0.0, 1.0), 0.0);
+        assert_eq!(throughput_score(-1.0, 1.0), 0.0);
+        assert_eq!(throughput_score(1.0, 0.0), 0.0);
+        assert_eq!(throughput_score(f64::NAN, 1.0), 0.0);
+    }
+
+    #[test]
+    fn workload_score_uses_the_workload_reference() {
+        let score = workload_score(
+            Workload::SingleThread,
+            3_600_000_000,
+            Duration::from_secs(2),
+        );
+        assert_close(score, REFERENCE_SCORE);
+        assert_eq!(
+            workload_score(Workload::SingleThread, 1, Duration::ZERO),
+            0.0
+        );
+        assert_eq!(
+            workload_score(Workload::Latency, 1, Duration::from_secs(1)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn composite_score_is_the_geometric_mean() {
+        assert_close(composite_score(&[1000.0]).unwrap(), 1000.0);
+        assert_close(composite_score(&[500.0, 2000.0]).unwrap(), 1000.0);
+        assert_close(composite_score(&[1.0, 10.0, 100.0]).unwrap(), 10.0);
+    }
+
+    #[test]
+    fn composite_score_needs_positive_finite_scores() {
+        assert_eq!(composite_score(&[]), None);
+        assert_eq!(composite_score(&[1000.0, 0.0]), None);
+        assert_eq!(composite_score(&[1000.0, -5.0]), None);
+        assert_eq!(composite_score(&[1000.0, f64::NAN]), None);
+        assert_eq!(composite_score(&[1000.0, f64::INFINITY]), None);
+    }
+
+    #[test]
+    fn megabytes_per_second_uses_decimal_megabytes() {
+        assert_close(
+            megabytes_per_second(3_000_000, Duration::from_millis(500)),
+            6.0,
+        );
+        assert_eq!(megabytes_per_second(1, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn every_reference_is_positive() {
+        for codec in CompressionCodec::ALL {
+            let (compress, decompress) = reference_compression_throughput(*codec);
+            assert!(compress > 0.0 && decompress > 0.0);
+        }
+        for size in AllocationSize::ALL {
+            assert!(reference_allocation_throughput(*size) > 0.0);
+        }
+        for kernel in AsyncKernel::ALL {
+            assert!(reference_async_throughput(*kernel) > 0.0);
+        }
+    }
+}
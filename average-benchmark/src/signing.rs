@@ -0,0 +1,85 @@
+use ed25519_dalek::{Signer, SigningKey};
+use log::warn;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use reqwest::header::{HeaderMap, HeaderValue};
+
+use average_benchmark::i18n::t;
+
+// Release key seed embedded by `build.rs` from the `SIGNING_KEY` variable at build time,
+// as the masked seed and its mask. `None` for development builds.
+include!(concat!(env!("OUT_DIR"), "/signing_key.rs"));
+
+// Overrides the release key at runtime, used to test against a local API.
+const SIGNING_KEY_ENV: &str = "AVERAGE_BENCHMARK_SIGNING_KEY";
+
+pub const TIMESTAMP_HEADER: &str = "x-benchmark-timestamp";
+pub const NONCE_HEADER: &str = "x-benchmark-nonce";
+pub const SIGNATURE_HEADER: &str = "x-benchmark-signature";
+pub const KEY_HEADER: &str = "x-benchmark-key";
+
+fn parse_signing_key(seed_hex: &str) -> Option<SigningKey> {
+    let mut seed = [0u8; 32];
+    hex::decode_to_slice(seed_hex.trim(), &mut seed).ok()?;
+    Some(SigningKey::from_bytes(&seed))
+}
+
+/// The key used to sign submissions: the runtime override if present, otherwise the
+/// release key. Builds without a key send unsigned submissions.
+pub fn signing_key() -> Option<SigningKey> {
+    if let Ok(seed_hex) = std::env::var(SIGNING_KEY_ENV) {
+        let key = parse_signing_key(&seed_hex);
+        if key.is_none() {
+            warn!("{}", t!("invalid-signing-key", variable = SIGNING_KEY_ENV));
+        }
+        return key;
+    }
+    let (masked, mask) = RELEASE_SIGNING_KEY?;
+    let mut seed = [0u8; 32];
+    for (byte, (masked, mask)) in seed.iter_mut().zip(masked.iter().zip(mask)) {
+        *byte = masked ^ mask;
+    }
+    Some(SigningKey::from_bytes(&seed))
+}
+
+/// Message covered by the signature. The API rebuilds it the same way:
+/// `"{timestamp}\n{nonce}\n"` followed by the exact request body.
+pub fn signed_message(timestamp: i64, nonce: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{}\n{}\n", timestamp, nonce).into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+/// Signature headers for one request. A fresh nonce and timestamp are used on every
+/// attempt, so retries are not rejected as replays.
+pub fn signature_headers(key: &SigningKey, body: &[u8]) -> HeaderMap {
+    let timestamp = chrono::Utc::now().timestamp();
+    let mut nonce = [0u8; 16];
+    OsRng.fill_bytes(&mut nonce);
+    let nonce = hex::encode(nonce);
+
+    let signature = key.sign(&signed_message(timestamp, &nonce, body));
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        (TIMESTAMP_HEADER, timestamp.to_string()),
+        (NONCE_HEADER, nonce),
+        (SIGNATURE_HEADER, hex::encode(signature.to_bytes())),
+        (KEY_HEADER, hex::encode(key.verifying_key().to_bytes())),
+    ] {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+/// Generates a new keypair for a local API and client, printed as hex.
+pub fn display_new_keypair() {
+    let key = SigningKey::generate(&mut OsRng);
+    println!("SIGNING_KEY={}", hex::encode(key.to_bytes()));
+    println!(
+        "SIGNING_PUBLIC_KEYS={}",
+        hex::encode(key.verifying_key().to_bytes())
+    );
+}
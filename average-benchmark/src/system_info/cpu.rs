@@ -0,0 +1,388 @@
+use log::warn;
+use std::collections::BTreeSet;
+use std::fs;
+
+use super::microarch;
+use crate::i18n::t;
+
+// Flag names as they appear in `/proc/cpuinfo` mapped to the SIMD extension they enable.
+const SIMD_FLAGS: &[(&str, &str)] = &[
+    ("sse2", "SSE2"),
+    ("ssse3", "SSSE3"),
+    ("sse4_1", "SSE4.1"),
+    ("sse4_2", "SSE4.2"),
+    ("avx", "AVX"),
+    ("avx2", "AVX2"),
+    ("fma", "FMA"),
+    ("avx_vnni", "AVX-VNNI"),
+    ("avx512f", "AVX-512F"),
+    ("avx512bw", "AVX-512BW"),
+    ("avx512_vnni", "AVX-512VNNI"),
+    ("avx512_bf16", "AVX-512BF16"),
+    ("amx_tile", "AMX"),
+    ("neon", "NEON"),
+    ("asimd", "NEON"),
+    ("asimddp", "DotProd"),
+    ("i8mm", "I8MM"),
+    ("sve", "SVE"),
+    ("sve2", "SVE2"),
+    ("sme", "SME"),
+];
+
+/// Identification details of the CPU beyond brand and vendor.
+#[derive(Debug, Default)]
+pub struct CpuIdentification {
+    pub flags: BTreeSet<String>,
+    pub family: Option<u32>,
+    pub model: Option<u32>,
+    pub stepping: Option<u32>,
+    pub microcode: Option<String>,
+    pub microarchitecture: Option<String>,
+    x86_vendor: Option<String>,
+    arm_implementer: Option<u32>,
+    arm_parts: Vec<u32>,
+}
+
+impl CpuIdentification {
+    /// SIMD extensions available according to the CPU flags, without duplicates.
+    pub fn simd_extensions(&self) -> Vec<&'static str> {
+        let mut extensions: Vec<&'static str> = Vec::new();
+        for (flag, name) in SIMD_FLAGS {
+            if self.flags.contains(*flag) && !extensions.contains(name) {
+                extensions.push(name);
+            }
+        }
+        extensions
+    }
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Parses the contents of `/proc/cpuinfo`. Only the first processor block is used for
+/// x86 fields, while ARM part numbers are collected from every block to describe
+/// big.LITTLE systems.
+pub fn parse_cpuinfo(contents: &str) -> CpuIdentification {
+    let mut identification = CpuIdentification::default();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        match key {
+            "flags" | "Features" if identification.flags.is_empty() => {
+                identification.flags = value.split_whitespace().map(str::to_string).collect();
+            }
+            "vendor_id" if identification.x86_vendor.is_none() => {
+                identification.x86_vendor = Some(value.to_string());
+            }
+            "cpu family" if identification.family.is_none() => {
+                identification.family = parse_number(value);
+            }
+            "model" if identification.model.is_none() => {
+                identification.model = parse_number(value);
+            }
+            "stepping" if identification.stepping.is_none() => {
+                identification.stepping = parse_number(value);
+            }
+            "microcode" if identification.microcode.is_none() => {
+                identification.microcode = Some(value.to_string());
+            }
+            "CPU implementer" if identification.arm_implementer.is_none() => {
+                identification.arm_implementer = parse_number(value);
+            }
+            "CPU architecture" if identification.family.is_none() => {
+                identification.family = parse_number(value);
+            }
+            "CPU variant" if identification.stepping.is_none() => {
+                identification.stepping = parse_number(value);
+            }
+            "CPU part" => {
+                if let Some(part) = parse_number(value) {
+                    if !identification.arm_parts.contains(&part) {
+                        identification.arm_parts.push(part);
+                    }
+                    if identification.model.is_none() {
+                        identification.model = Some(part);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    identification
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn fill_from_cpuid(identification: &mut CpuIdentification) {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // SAFETY: `cpuid` leaves 0 and 1 are available on every x86 CPU able to run this binary.
+    let (leaf0, leaf1) = unsafe { (__cpuid(0), __cpuid(1)) };
+
+    if identification.x86_vendor.is_none() {
+        let mut vendor = Vec::with_capacity(12);
+        for register in [leaf0.ebx, leaf0.edx, leaf0.ecx] {
+            vendor.extend_from_slice(&register.to_le_bytes());
+        }
+        identification.x86_vendor = Some(String::from_utf8_lossy(&vendor).into_owned());
+    }
+
+    if identification.family.is_none() {
+        let base_family = (leaf1.eax >> 8) & 0xF;
+        let base_model = (leaf1.eax >> 4) & 0xF;
+        let extended_family = (leaf1.eax >> 20) & 0xFF;
+        let extended_model = (leaf1.eax >> 16) & 0xF;
+
+        let family = if base_family == 0xF {
+            base_family + extended_family
+        } else {
+            base_family
+        };
+        let model = if base_family == 0x6 || base_family == 0xF {
+            (extended_model << 4) + base_model
+        } else {
+            base_model
+        };
+
+        identification.family = Some(family);
+        identification.model = Some(model);
+        identification.stepping = Some(leaf1.eax & 0xF);
+    }
+
+    if identification.flags.is_empty() {
+        macro_rules! detect {
+            ($($feature:tt => $flag:expr),* $(,)?) => {
+                $(
+                    if std::is_x86_feature_detected!($feature) {
+                        identification.flags.insert($flag.to_string());
+                    }
+                )*
+            };
+        }
+        detect! {
+            "sse2" => "sse2",
+            "ssse3" => "ssse3",
+            "sse4.1" => "sse4_1",
+            "sse4.2" => "sse4_2",
+            "avx" => "avx",
+            "avx2" => "avx2",
+            "fma" => "fma",
+            "avx512f" => "avx512f",
+            "avx512bw" => "avx512bw",
+            "avx512vnni" => "avx512_vnni",
+            "aes" => "aes",
+            "sha" => "sha_ni",
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn fill_from_runtime_detection(identification: &mut CpuIdentification) {
+    if !identification.flags.is_empty() {
+        return;
+    }
+    macro_rules! detect {
+        ($($feature:tt => $flag:expr),* $(,)?) => {
+            $(
+                if std::arch::is_aarch64_feature_detected!($feature) {
+                    identification.flags.insert($flag.to_string());
+                }
+            )*
+        };
+    }
+    detect! {
+        "neon" => "asimd",
+        "dotprod" => "asimddp",
+        "i8mm" => "i8mm",
+        "sve" => "sve",
+        "sve2" => "sve2",
+        "aes" => "aes",
+        "sha2" => "sha2",
+    }
+}
+
+fn resolve_microarchitecture(identification: &CpuIdentification) -> Option<String> {
+    if let (Some(vendor), Some(family), Some(model)) = (
+        identification.x86_vendor.as_deref(),
+        identification.family,
+        identification.model,
+    ) {
+        return microarch::lookup_x86(vendor, family, model).map(str::to_string);
+    }
+
+    let implementer = identification.arm_implementer?;
+    let mut names: Vec<&str> = Vec::new();
+    for part in &identification.arm_parts {
+        if let Some(name) = microarch::lookup_arm(implementer, *part) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    if names.is_empty() {
+        None
+    } else {
+        Some(names.join(" + "))
+    }
+}
+
+/// Identifies the CPU from `/proc/cpuinfo`, falling back to `cpuid` and runtime feature
+/// detection where the file is not available (macOS, Windows).
+pub fn identify_cpu() -> CpuIdentification {
+    let cpuinfo_path = "/proc/cpuinfo";
+    let mut identification = match fs::read_to_string(cpuinfo_path) {
+        Ok(contents) => parse_cpuinfo(&contents),
+        Err(e) => {
+            warn!(
+                "{}",
+                t!(
+                    "read-file-error",
+                    path = cpuinfo_path.to_string(),
+                    error = format!("{:?}", e)
+                )
+            );
+            CpuIdentification::default()
+        }
+    };
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    fill_from_cpuid(&mut identification);
+    #[cfg(target_arch = "aarch64")]
+    fill_from_runtime_detection(&mut identification);
+
+    if identification.microcode.is_none() {
+        identification.microcode =
+            fs::read_to_string("/sys/devices/system/cpu/cpu0/microcode/version")
+                .ok()
+                .map(|version| version.trim().to_string())
+                .filter(|version| !version.is_empty());
+    }
+
+    identification.microarchitecture = resolve_microarchitecture(&identification);
+    identification
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTEL_CPUINFO: &str = "\
+processor\t: 0
+vendor_id\t: GenuineIntel
+cpu family\t: 6
+model\t\t: 207
+model name\t: Intel(R) Xeon(R) Processor @ 2.10GHz
+stepping\t: 2
+microcode\t: 0x21000230
+flags\t\t: fpu sse2 ssse3 sse4_1 sse4_2 avx avx2 fma avx512f avx512bw amx_tile
+
+processor\t: 1
+vendor_id\t: GenuineIntel
+cpu family\t: 6
+model\t\t: 143
+stepping\t: 8
+flags\t\t: fpu sse2
+";
+
+    const ARM_CPUINFO: &str = "\
+processor\t: 0
+Features\t: fp asimd aes sha2 asimddp
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x1
+CPU part\t: 0xd05
+
+processor\t: 1
+Features\t: fp asimd aes sha2 asimddp
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x3
+CPU part\t: 0xd0b
+
+processor\t: 2
+Features\t: fp asimd aes sha2 asimddp
+CPU implementer\t: 0x41
+CPU architecture: 8
+CPU variant\t: 0x3
+CPU part\t: 0xd0b
+";
+
+    #[test]
+    fn parses_decimal_and_hex_numbers() {
+        assert_eq!(parse_number("207"), Some(207));
+        assert_eq!(parse_number("0xd0b"), Some(0xD0B));
+        assert_eq!(parse_number("0xzz"), None);
+        assert_eq!(parse_number(""), None);
+    }
+
+    #[test]
+    fn x86_fields_come_from_the_first_processor() {
+        let identification = parse_cpuinfo(INTEL_CPUINFO);
+        assert_eq!(identification.x86_vendor.as_deref(), Some("GenuineIntel"));
+        assert_eq!(identification.family, Some(6));
+        assert_eq!(identification.model, Some(207));
+        assert_eq!(identification.stepping, Some(2));
+        assert_eq!(identification.microcode.as_deref(), Some("0x21000230"));
+        assert!(identification.flags.contains("amx_tile"));
+        assert_eq!(
+            resolve_microarchitecture(&identification).as_deref(),
+            Some("Raptor Cove")
+        );
+    }
+
+    #[test]
+    fn arm_parts_are_collected_from_every_processor() {
+        let identification = parse_cpuinfo(ARM_CPUINFO);
+        assert_eq!(identification.arm_implementer, Some(0x41));
+        assert_eq!(identification.arm_parts, [0xD05, 0xD0B]);
+        assert_eq!(identification.family, Some(8));
+        assert_eq!(identification.model, Some(0xD05));
+        assert_eq!(identification.stepping, Some(1));
+        assert_eq!(
+            resolve_microarchitecture(&identification).as_deref(),
+            Some("Cortex-A55 + Cortex-A76")
+        );
+    }
+
+    #[test]
+    fn unknown_cpus_have_no_microarchitecture() {
+        assert_eq!(resolve_microarchitecture(&parse_cpuinfo("")), None);
+        let identification = parse_cpuinfo("vendor_id : GenuineIntel\ncpu family : 6\nmodel : 1\n");
+        assert_eq!(resolve_microarchitecture(&identification), None);
+    }
+
+    #[test]
+    fn simd_extensions_are_deduplicated_in_table_order() {
+        let identification = parse_cpuinfo(ARM_CPUINFO);
+        assert_eq!(identification.simd_extensions(), ["NEON", "DotProd"]);
+        let identification = parse_cpuinfo(INTEL_CPUINFO);
+        assert_eq!(
+            identification.simd_extensions(),
+            [
+                "SSE2",
+                "SSSE3",
+                "SSE4.1",
+                "SSE4.2",
+                "AVX",
+                "AVX2",
+                "FMA",
+                "AVX-512F",
+                "AVX-512BW",
+                "AMX"
+            ]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::fs;
+use sysinfo::System;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Memory, kernel and build details that influence benchmark results.
+#[derive(Debug)]
+pub struct EnvironmentInfo {
+    pub memory_total_mb: u64,
+    pub memory_available_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_used_mb: u64,
+    pub transparent_hugepage: Option<String>,
+    pub kernel_version: Option<String>,
+    pub kernel_mitigations: Option<String>,
+    pub cpu_vulnerabilities: BTreeMap<String, String>,
+    pub cpu_governor: Option<String>,
+    pub cpu_energy_performance_preference: Option<String>,
+    pub build_rustc: &'static str,
+    pub build_target: &'static str,
+    pub build_profile: &'static str,
+    pub build_allocator: &'static str,
+}
+
+pub(super) fn read_trimmed(path: &str) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Sysfs files like `transparent_hugepage/enabled` list every option and mark the
+/// active one with brackets: `always [madvise] never`.
+pub(super) fn selected_option(value: &str) -> &str {
+    value
+        .split_whitespace()
+        .find_map(|option| option.strip_prefix('[')?.strip_suffix(']'))
+        .unwrap_or(value)
+}
+
+fn get_kernel_mitigations() -> Option<String> {
+    let cmdline = read_trimmed("/proc/cmdline")?;
+    cmdline
+        .split_whitespace()
+        .find_map(|param| param.strip_prefix("mitigations="))
+        .map(str::to_string)
+}
+
+fn get_cpu_vulnerabilities() -> BTreeMap<String, String> {
+    let mut vulnerabilities = BTreeMap::new();
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu/vulnerabilities") else {
+        return vulnerabilities;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Ok(status) = fs::read_to_string(entry.path()) {
+            vulnerabilities.insert(name, status.trim().to_string());
+        }
+    }
+    vulnerabilities
+}
+
+pub fn get_environment_info(sys: &System) -> EnvironmentInfo {
+    EnvironmentInfo {
+        memory_total_mb: sys.total_memory() / BYTES_PER_MB,
+        memory_available_mb: sys.available_memory() / BYTES_PER_MB,
+        swap_total_mb: sys.total_swap() / BYTES_PER_MB,
+        swap_used_mb: sys.used_swap() / BYTES_PER_MB,
+        transparent_hugepage: read_trimmed("/sys/kernel/mm/transparent_hugepage/enabled")
+            .map(|value| selected_option(&value).to_string()),
+        kernel_version: System::kernel_version(),
+        kernel_mitigations: get_kernel_mitigations(),
+        cpu_vulnerabilities: get_cpu_vulnerabilities(),
+        cpu_governor: read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+        cpu_energy_performance_preference: read_trimmed(
+            "/sys/devices/system/cpu/cpu0/cpufreq/energy_performance_preference",
+        ),
+        build_rustc: env!("BUILD_RUSTC_VERSION"),
+        build_target: env!("BUILD_TARGET"),
+        build_profile: env!("BUILD_PROFILE"),
+        build_allocator: crate::benchmark::allocator::GLOBAL_ALLOCATOR,
+    }
+}
@@ -0,0 +1,188 @@
+// Best-effort lookup table from CPU identification to core microarchitecture.
+// x86 entries are keyed by vendor, display family and a display model range;
+// ARM entries by the MIDR implementer and part number.
+
+const X86_MICROARCHITECTURES: &[(&str, u32, u32, u32, &str)] = &[
+    // Intel Core
+    ("GenuineIntel", 6, 0x1A, 0x1A, "Nehalem"),
+    ("GenuineIntel", 6, 0x1E, 0x1F, "Nehalem"),
+    ("GenuineIntel", 6, 0x2E, 0x2E, "Nehalem"),
+    ("GenuineIntel", 6, 0x25, 0x25, "Westmere"),
+    ("GenuineIntel", 6, 0x2C, 0x2C, "Westmere"),
+    ("GenuineIntel", 6, 0x2F, 0x2F, "Westmere"),
+    ("GenuineIntel", 6, 0x2A, 0x2A, "Sandy Bridge"),
+    ("GenuineIntel", 6, 0x2D, 0x2D, "Sandy Bridge"),
+    ("GenuineIntel", 6, 0x3A, 0x3A, "Ivy Bridge"),
+    ("GenuineIntel", 6, 0x3E, 0x3E, "Ivy Bridge"),
+    ("GenuineIntel", 6, 0x3C, 0x3C, "Haswell"),
+    ("GenuineIntel", 6, 0x3F, 0x3F, "Haswell"),
+    ("GenuineIntel", 6, 0x45, 0x46, "Haswell"),
+    ("GenuineIntel", 6, 0x3D, 0x3D, "Broadwell"),
+    ("GenuineIntel", 6, 0x47, 0x47, "Broadwell"),
+    ("GenuineIntel", 6, 0x4F, 0x4F, "Broadwell"),
+    ("GenuineIntel", 6, 0x56, 0x56, "Broadwell"),
+    ("GenuineIntel", 6, 0x4E, 0x4E, "Skylake"),
+    ("GenuineIntel", 6, 0x55, 0x55, "Skylake"),
+    ("GenuineIntel", 6, 0x5E, 0x5E, "Skylake"),
+    ("GenuineIntel", 6, 0x8E, 0x8E, "Skylake"),
+    ("GenuineIntel", 6, 0x9E, 0x9E, "Skylake"),
+    ("GenuineIntel", 6, 0xA5, 0xA6, "Skylake"),
+    ("GenuineIntel", 6, 0x66, 0x66, "Palm Cove"),
+    ("GenuineIntel", 6, 0x6A, 0x6A, "Sunny Cove"),
+    ("GenuineIntel", 6, 0x6C, 0x6C, "Sunny Cove"),
+    ("GenuineIntel", 6, 0x7D, 0x7E, "Sunny Cove"),
+    ("GenuineIntel", 6, 0x8C, 0x8D, "Willow Cove"),
+    ("GenuineIntel", 6, 0xA7, 0xA7, "Cypress Cove"),
+    ("GenuineIntel", 6, 0x8F, 0x8F, "Golden Cove"),
+    ("GenuineIntel", 6, 0x97, 0x97, "Golden Cove + Gracemont"),
+    ("GenuineIntel", 6, 0x9A, 0x9A, "Golden Cove + Gracemont"),
+    ("GenuineIntel", 6, 0xB7, 0xB7, "Raptor Cove + Gracemont"),
+    ("GenuineIntel", 6, 0xBA, 0xBA, "Raptor Cove + Gracemont"),
+    ("GenuineIntel", 6, 0xBF, 0xBF, "Raptor Cove + Gracemont"),
+    ("GenuineIntel", 6, 0xCF, 0xCF, "Raptor Cove"),
+    ("GenuineIntel", 6, 0xAA, 0xAA, "Redwood Cove + Crestmont"),
+    ("GenuineIntel", 6, 0xAC, 0xAC, "Redwood Cove + Crestmont"),
+    ("GenuineIntel", 6, 0xAD, 0xAE, "Redwood Cove"),
+    ("GenuineIntel", 6, 0xBD, 0xBD, "Lion Cove + Skymont"),
+    ("GenuineIntel", 6, 0xC5, 0xC6, "Lion Cove + Skymont"),
+    // Intel Atom
+    ("GenuineIntel", 6, 0x5C, 0x5C, "Goldmont"),
+    ("GenuineIntel", 6, 0x5F, 0x5F, "Goldmont"),
+    ("GenuineIntel", 6, 0x7A, 0x7A, "Goldmont Plus"),
+    ("GenuineIntel", 6, 0x86, 0x86, "Tremont"),
+    ("GenuineIntel", 6, 0x96, 0x96, "Tremont"),
+    ("GenuineIntel", 6, 0x9C, 0x9C, "Tremont"),
+    ("GenuineIntel", 6, 0xBE, 0xBE, "Gracemont"),
+    ("GenuineIntel", 6, 0xAF, 0xAF, "Crestmont"),
+    ("GenuineIntel", 6, 0xDD, 0xDD, "Darkmont"),
+    // AMD
+    ("AuthenticAMD", 0x15, 0x00, 0x0F, "Bulldozer"),
+    ("AuthenticAMD", 0x15, 0x10, 0x1F, "Piledriver"),
+    ("AuthenticAMD", 0x15, 0x30, 0x3F, "Steamroller"),
+    ("AuthenticAMD", 0x15, 0x60, 0x7F, "Excavator"),
+    ("AuthenticAMD", 0x16, 0x00, 0x0F, "Jaguar"),
+    ("AuthenticAMD", 0x16, 0x30, 0x3F, "Puma"),
+    ("AuthenticAMD", 0x17, 0x08, 0x08, "Zen+"),
+    ("AuthenticAMD", 0x17, 0x18, 0x18, "Zen+"),
+    ("AuthenticAMD", 0x17, 0x00, 0x2F, "Zen"),
+    ("AuthenticAMD", 0x17, 0x30, 0xFF, "Zen 2"),
+    ("AuthenticAMD", 0x19, 0x00, 0x0F, "Zen 3"),
+    ("AuthenticAMD", 0x19, 0x10, 0x1F, "Zen 4"),
+    ("AuthenticAMD", 0x19, 0x20, 0x2F, "Zen 3"),
+    ("AuthenticAMD", 0x19, 0x40, 0x4F, "Zen 3+"),
+    ("AuthenticAMD", 0x19, 0x50, 0x5F, "Zen 3"),
+    ("AuthenticAMD", 0x19, 0x60, 0x7F, "Zen 4"),
+    ("AuthenticAMD", 0x19, 0xA0, 0xAF, "Zen 4c"),
+    ("AuthenticAMD", 0x1A, 0x00, 0xFF, "Zen 5"),
+    ("HygonGenuine", 0x18, 0x00, 0xFF, "Dhyana (Zen)"),
+];
+
+const ARM_MICROARCHITECTURES: &[(u32, u32, &str)] = &[
+    // Arm Ltd.
+    (0x41, 0xD03, "Cortex-A53"),
+    (0x41, 0xD04, "Cortex-A35"),
+    (0x41, 0xD05, "Cortex-A55"),
+    (0x41, 0xD06, "Cortex-A65"),
+    (0x41, 0xD07, "Cortex-A57"),
+    (0x41, 0xD08, "Cortex-A72"),
+    (0x41, 0xD09, "Cortex-A73"),
+    (0x41, 0xD0A, "Cortex-A75"),
+    (0x41, 0xD0B, "Cortex-A76"),
+    (0x41, 0xD0C, "Neoverse N1"),
+    (0x41, 0xD0D, "Cortex-A77"),
+    (0x41, 0xD0E, "Cortex-A76AE"),
+    (0x41, 0xD40, "Neoverse V1"),
+    (0x41, 0xD41, "Cortex-A78"),
+    (0x41, 0xD44, "Cortex-X1"),
+    (0x41, 0xD46, "Cortex-A510"),
+    (0x41, 0xD47, "Cortex-A710"),
+    (0x41, 0xD48, "Cortex-X2"),
+    (0x41, 0xD49, "Neoverse N2"),
+    (0x41, 0xD4A, "Neoverse E1"),
+    (0x41, 0xD4B, "Cortex-A78C"),
+    (0x41, 0xD4D, "Cortex-A715"),
+    (0x41, 0xD4E, "Cortex-X3"),
+    (0x41, 0xD4F, "Neoverse V2"),
+    (0x41, 0xD80, "Cortex-A520"),
+    (0x41, 0xD81, "Cortex-A720"),
+    (0x41, 0xD82, "Cortex-X4"),
+    (0x41, 0xD84, "Neoverse V3"),
+    (0x41, 0xD85, "Cortex-X925"),
+    (0x41, 0xD87, "Cortex-A725"),
+    (0x41, 0xD8E, "Neoverse N3"),
+    // Fujitsu
+    (0x46, 0x001, "A64FX"),
+    // Qualcomm
+    (0x51, 0x800, "Kryo 2xx Gold"),
+    (0x51, 0x801, "Kryo 2xx Silver"),
+    (0x51, 0x802, "Kryo 3xx Gold"),
+    (0x51, 0x803, "Kryo 3xx Silver"),
+    (0x51, 0x804, "Kryo 4xx Gold"),
+    (0x51, 0x805, "Kryo 4xx Silver"),
+    (0x51, 0x001, "Oryon"),
+    // Apple
+    (0x61, 0x022, "Icestorm"),
+    (0x61, 0x023, "Firestorm"),
+    (0x61, 0x032, "Blizzard"),
+    (0x61, 0x033, "Avalanche"),
+    // Ampere
+    (0xC0, 0xAC3, "Ampere-1"),
+    (0xC0, 0xAC4, "Ampere-1a"),
+];
+
+pub fn lookup_x86(vendor: &str, family: u32, model: u32) -> Option<&'static str> {
+    X86_MICROARCHITECTURES
+        .iter()
+        .find(|(v, f, lo, hi, _)| *v == vendor && *f == family && (*lo..=*hi).contains(&model))
+        .map(|(_, _, _, _, name)| *name)
+}
+
+pub fn lookup_arm(implementer: u32, part: u32) -> Option<&'static str> {
+    ARM_MICROARCHITECTURES
+        .iter()
+        .find(|(i, p, _)| *i == implementer && *p == part)
+        .map(|(_, _, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn x86_lookup_matches_vendor_family_and_model_range() {
+        assert_eq!(lookup_x86("GenuineIntel", 6, 0xCF), Some("Raptor Cove"));
+        assert_eq!(lookup_x86("AuthenticAMD", 0x19, 0x61), Some("Zen 4"));
+        assert_eq!(lookup_x86("AuthenticAMD", 0x1A, 0xFF), Some("Zen 5"));
+        assert_eq!(lookup_x86("AuthenticAMD", 6, 0xCF), None);
+        assert_eq!(lookup_x86("GenuineIntel", 0x19, 0x61), None);
+    }
+
+    #[test]
+    fn x86_lookup_prefers_earlier_single_model_entries() {
+        assert_eq!(lookup_x86("AuthenticAMD", 0x17, 0x08), Some("Zen+"));
+        assert_eq!(lookup_x86("AuthenticAMD", 0x17, 0x01), Some("Zen"));
+    }
+
+    #[test]
+    fn arm_lookup_matches_implementer_and_part() {
+        assert_eq!(lookup_arm(0x41, 0xD0C), Some("Neoverse N1"));
+        assert_eq!(lookup_arm(0x61, 0x023), Some("Firestorm"));
+        assert_eq!(lookup_arm(0x51, 0xD0C), None);
+    }
+
+    #[test]
+    fn tables_have_well_formed_entries() {
+        for (vendor, _, lo, hi, name) in X86_MICROARCHITECTURES {
+            assert!(lo <= hi, "{vendor} {name}: {lo:#x} > {hi:#x}");
+            assert!(!name.is_empty());
+        }
+        for (i, (implementer, part, _)) in ARM_MICROARCHITECTURES.iter().enumerate() {
+            assert!(
+                !ARM_MICROARCHITECTURES[..i]
+                    .iter()
+                    .any(|(j, p, _)| j == implementer && p == part),
+                "duplicate ARM entry {implementer:#x}/{part:#x}"
+            );
+        }
+    }
+}
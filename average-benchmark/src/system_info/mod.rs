@@ -0,0 +1,401 @@
+use log::{info, warn};
+use serde_json::json;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, BufRead};
+use std::process::Command;
+use sysinfo::System;
+
+use crate::i18n::t;
+use crate::runner::BenchmarkReport;
+
+mod cpu;
+mod environment;
+mod microarch;
+mod storage;
+mod virtualization;
+
+pub use storage::{get_storage_info, StorageInfo};
+pub use virtualization::{get_virtualization_info, VirtualizationInfo};
+
+fn run_command_output(cmd: &str, args: &[&str]) -> Option<String> {
+    match Command::new(cmd).args(args).output() {
+        Ok(output) => {
+            let out = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if out.is_empty() {
+                warn!(
+                    "{}",
+                    t!(
+                        "command-empty-output",
+                        command = cmd.to_string(),
+                        args = format!("{:?}", args)
+                    )
+                );
+                None
+            } else {
+                Some(out)
+            }
+        }
+        Err(e) => {
+            warn!(
+                "{}",
+                t!(
+                    "command-error",
+                    command = cmd.to_string(),
+                    args = format!("{:?}", args),
+                    error = format!("{:?}", e)
+                )
+            );
+            None
+        }
+    }
+}
+
+fn get_prop(prop: &str) -> Option<String> {
+    run_command_output("getprop", &[prop])
+}
+
+#[derive(Debug)]
+struct CpuInfo {
+    cpu_brand: Option<String>,
+    cpu_frequency: Option<u64>,
+    cpu_vendor_id: Option<String>,
+    number_of_cpus: usize,
+}
+
+fn get_max_cpu_frequency() -> Option<u64> {
+    let mut max_freq = 0;
+    for i in 0..64 {
+        let path = format!("/sys/devices/system/cpu/cpu{}/cpufreq/scaling_max_freq", i);
+        if let Ok(freq_str) = fs::read_to_string(&path) {
+            if let Ok(freq_khz) = freq_str.trim().parse::<u64>() {
+                max_freq = max_freq.max(freq_khz);
+            }
+        }
+    }
+    if max_freq > 0 {
+        Some(max_freq / 1000)
+    } else {
+        None
+    }
+}
+
+fn get_cpu_info_fallback() -> CpuInfo {
+    let cpuinfo_path = "/proc/cpuinfo";
+    let mut cpu_brand: Option<String> = None;
+    let mut number_of_cpus = 0;
+
+    match fs::File::open(cpuinfo_path) {
+        Ok(file) => {
+            let reader = io::BufReader::new(file);
+            for line in reader.lines().map_while(Result::ok) {
+                if line.starts_with("processor") {
+                    number_of_cpus += 1;
+                }
+                if line.starts_with("Hardware") && cpu_brand.is_none() {
+                    if let Some(index) = line.find(':') {
+                        let value = line[index + 1..].trim();
+                        if !value.is_empty() {
+                            cpu_brand = Some(value.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            warn!(
+                "{}",
+                t!(
+                    "read-file-error",
+                    path = cpuinfo_path.to_string(),
+                    error = format!("{:?}", e)
+                )
+            );
+        }
+    }
+
+    if number_of_cpus == 0 {
+        number_of_cpus = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+    }
+
+    let cpu_frequency = get_max_cpu_frequency();
+
+    if cpu_brand.is_none() {
+        cpu_brand = get_prop("ro.soc.model");
+    }
+
+    let cpu_vendor_id = get_prop("ro.soc.manufacturer");
+
+    CpuInfo {
+        cpu_brand,
+        cpu_frequency,
+        cpu_vendor_id,
+        number_of_cpus,
+    }
+}
+
+/// Builds the submission payload: the system description and the results of `report`.
+pub fn get_system_info(report: &BenchmarkReport, virtualization: &VirtualizationInfo) -> Value {
+    let mut sys = System::new_all();
+    sys.refresh_all();
+
+    let system_name = System::name().unwrap_or_else(|| {
+        warn!("{}", t!("system-name-unavailable"));
+        "Unknown".to_string()
+    });
+    let system_os_version = System::os_version().unwrap_or_else(|| {
+        warn!("{}", t!("os-version-unavailable"));
+        "Unknown".to_string()
+    });
+    let combined_system_info = format!("{} {}", system_name, system_os_version);
+
+    let sysinfo_cpus = sys.cpus();
+    let num_cpus = sysinfo_cpus.len();
+    let sys_cpu_brand = sysinfo_cpus.first().map(|cpu| cpu.brand().to_string());
+
+    let max_sys_cpu_frequency = sys.cpus().iter().map(|cpu| cpu.frequency()).max();
+    let sys_vendor_id = sys.cpus().first().map(|cpu| cpu.vendor_id().to_string());
+
+    let fallback = get_cpu_info_fallback();
+
+    let final_cpu_vendor = match sys_vendor_id {
+        Some(ref v) if !v.is_empty() && v != "Unknown Vendor" => v.clone(),
+        _ => fallback
+            .cpu_vendor_id
+            .unwrap_or_else(|| "Unknown Vendor".to_string()),
+    };
+
+    let final_cpu_brand = sys_cpu_brand
+        .filter(|s| !s.is_empty())
+        .or(fallback.cpu_brand);
+
+    let final_cpu_frequency = match max_sys_cpu_frequency {
+        Some(freq) if freq > 0 => Some(freq),
+        _ => fallback.cpu_frequency,
+    };
+
+    let final_number_of_cpus = if num_cpus == 0 {
+        fallback.number_of_cpus
+    } else {
+        num_cpus
+    };
+
+    let identification = cpu::identify_cpu();
+    let cpu_simd = identification.simd_extensions();
+    let environment = environment::get_environment_info(&sys);
+
+    let host_name = System::host_name().unwrap_or_else(|| "unknown".to_string());
+
+    info!(
+        "{}",
+        t!("log-system-info", system = combined_system_info.clone())
+    );
+    info!("{}", t!("log-number-of-cpus", cpus = final_number_of_cpus));
+    info!("CPU Vendor: {:?}", final_cpu_vendor);
+    info!("CPU Brand: {:?}", final_cpu_brand);
+    info!("CPU Frequency: {:?}", final_cpu_frequency);
+    info!(
+        "CPU Family/Model/Stepping: {:?}/{:?}/{:?}",
+        identification.family, identification.model, identification.stepping
+    );
+    info!("CPU Microcode: {:?}", identification.microcode);
+    info!(
+        "CPU Microarchitecture: {:?}",
+        identification.microarchitecture
+    );
+    info!("CPU SIMD: {:?}", cpu_simd);
+    info!(
+        "{}",
+        t!(
+            "log-memory",
+            total = environment.memory_total_mb,
+            available = environment.memory_available_mb,
+            swap_used = environment.swap_used_mb,
+            swap_total = environment.swap_total_mb
+        )
+    );
+    info!("Kernel: {:?}", environment.kernel_version);
+    info!("Hypervisor: {:?}", virtualization.hypervisor);
+    info!(
+        "{}",
+        t!(
+            "log-container",
+            runtime = format!("{:?}", virtualization.container_runtime)
+        )
+    );
+    info!(
+        "{}",
+        t!(
+            "log-cgroup",
+            version = format!("{:?}", virtualization.cgroup_version),
+            quota = format!("{:?}", virtualization.cgroup_cpu_quota),
+            cpuset = format!("{:?}", virtualization.cgroup_cpuset_cpus),
+            parallelism = virtualization.effective_parallelism
+        )
+    );
+    info!("CPU Governor: {:?}", environment.cpu_governor);
+    info!(
+        "{}",
+        t!(
+            "log-build",
+            rustc = environment.build_rustc,
+            target = environment.build_target,
+            profile = environment.build_profile,
+            allocator = environment.build_allocator
+        )
+    );
+
+    let score = |score: Option<f64>| score.map(|score| format!("{:.3}", score));
+    let mut payload = json!({
+        "system_info": combined_system_info,
+        "system_host_name": host_name,
+        "number_of_cpus": final_number_of_cpus,
+        "cpu_vendor_id": final_cpu_vendor,
+        "cpu_brand": final_cpu_brand,
+        "cpu_frequency": final_cpu_frequency,
+        "cpu_family": identification.family,
+        "cpu_model": identification.model,
+        "cpu_stepping": identification.stepping,
+        "cpu_microcode": identification.microcode,
+        "cpu_microarchitecture": identification.microarchitecture,
+        "cpu_simd": cpu_simd,
+        "cpu_flags": identification.flags,
+        "memory_total_mb": environment.memory_total_mb,
+        "memory_available_mb": environment.memory_available_mb,
+        "swap_total_mb": environment.swap_total_mb,
+        "swap_used_mb": environment.swap_used_mb,
+        "transparent_hugepage": environment.transparent_hugepage,
+        "kernel_version": environment.kernel_version,
+        "kernel_mitigations": environment.kernel_mitigations,
+        "cpu_vulnerabilities": environment.cpu_vulnerabilities,
+        "cpu_governor": environment.cpu_governor,
+        "cpu_energy_performance_preference": environment.cpu_energy_performance_preference,
+        "build_rustc": environment.build_rustc,
+        "build_target": environment.build_target,
+        "build_profile": environment.build_profile,
+        "build_allocator": environment.build_allocator,
+        "hypervisor": virtualization.hypervisor,
+        "container_runtime": virtualization.container_runtime,
+        "cgroup_version": virtualization.cgroup_version,
+        "cgroup_cpu_quota": virtualization.cgroup_cpu_quota,
+        "cgroup_cpuset_cpus": virtualization.cgroup_cpuset_cpus,
+        "effective_parallelism": virtualization.effective_parallelism,
+        "virtualized": virtualization.is_virtualized(),
+        "constrained": virtualization.is_constrained(),
+        "score_single_thread": score(report.single_thread.as_ref().map(|result| result.score)),
+        "score_multi_thread": score(report.multi_thread.as_ref().map(|result| result.score)),
+        "score_composite": score(report.score_composite),
+        "scoring_model_version": report.scoring_model_version,
+        "profile": report.profile.as_str(),
+        "profile_version": report.profile_version,
+    });
+
+    if let Some(fields) = payload.as_object_mut() {
+        // Scores of the workloads added after the arithmetic ones, null when not run
+        for (field, workload_score) in [
+            (
+                "score_crypto",
+                report.crypto.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_compression",
+                report.compression.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_integer",
+                report.integer.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_numeric",
+                report.numeric.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_text",
+                report.text.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_allocator",
+                report.allocator.as_ref().and_then(|result| result.score),
+            ),
+            (
+                "score_async_runtime",
+                report
+                    .async_runtime
+                    .as_ref()
+                    .and_then(|result| result.score),
+            ),
+        ] {
+            fields.insert(field.to_string(), json!(score(workload_score)));
+        }
+        if !report.telemetry.is_empty() {
+            fields.insert("telemetry".to_string(), json!(report.telemetry));
+        }
+        if let Some(crypto) = &report.crypto {
+            fields.insert("crypto".to_string(), json!(crypto.algorithms));
+        }
+        if let Some(compression) = &report.compression {
+            fields.insert(
+                "compression".to_string(),
+                json!({
+                    "bytes": compression.bytes,
+                    "threads": compression.threads,
+                    "single_thread": compression.single_thread,
+                    "multi_thread": compression.multi_thread,
+                }),
+            );
+        }
+        if let Some(integer) = &report.integer {
+            fields.insert("integer".to_string(), json!(integer.kernels));
+        }
+        if let Some(numeric) = &report.numeric {
+            fields.insert(
+                "numeric".to_string(),
+                json!({
+                    "threads": numeric.threads,
+                    "single_thread": numeric.single_thread,
+                    "multi_thread": numeric.multi_thread,
+                }),
+            );
+        }
+        if let Some(text) = &report.text {
+            fields.insert("text".to_string(), json!(text.kernels));
+        }
+        if let Some(allocator) = &report.allocator {
+            fields.insert(
+                "allocator".to_string(),
+                json!({
+                    "threads": allocator.threads,
+                    "single_thread": allocator.single_thread,
+                    "multi_thread": allocator.multi_thread,
+                    "page_faults": allocator.page_faults,
+                }),
+            );
+        }
+        if let Some(async_runtime) = &report.async_runtime {
+            fields.insert("async_runtime".to_string(), json!(async_runtime.points));
+        }
+        if let Some(os_overhead) = &report.os_overhead {
+            fields.insert("os_overhead".to_string(), json!(os_overhead.kernels));
+        }
+        if let Some(storage) = &report.storage {
+            fields.insert("storage_device".to_string(), json!(storage.device));
+            fields.insert(
+                "storage".to_string(),
+                json!({
+                    "file_bytes": storage.file_bytes,
+                    "measurements": storage.measurements,
+                    "error": storage.error,
+                }),
+            );
+        }
+        if let Some(latency) = &report.latency {
+            fields.insert("latency".to_string(), json!(latency));
+        }
+        if !report.scaling.is_empty() {
+            fields.insert("scaling".to_string(), json!(report.scaling));
+        }
+    }
+    payload
+}
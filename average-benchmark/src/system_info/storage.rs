@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::environment::{read_trimmed, selected_option};
+
+/// Filesystem and block device that hold a directory, as found in `/proc/mounts` and
+/// sysfs. Every field is `None` when they can't be found, e.g. outside of Linux.
+#[derive(Debug, Default, Serialize)]
+pub struct StorageInfo {
+    /// Filesystem type, such as `ext4`, `xfs`, `btrfs`, `overlay` or `tmpfs`.
+    pub filesystem: Option<String>,
+    /// Whole disk behind the filesystem, such as `nvme0n1`, `sda` or `dm-0` for device
+    /// mapper volumes. `None` for filesystems without one, like `tmpfs`.
+    pub block_device: Option<String>,
+    pub model: Option<String>,
+    /// Whether the kernel considers the disk a spinning one.
+    pub rotational: Option<bool>,
+    /// Active I/O scheduler of the disk.
+    pub scheduler: Option<String>,
+}
+
+/// `/proc/mounts` escapes spaces, tabs, newlines and backslashes of the paths in octal.
+fn unescape_mount_path(path: &str) -> String {
+    path.replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+/// Type of the filesystem mounted on the deepest mount point that contains `directory`.
+fn filesystem_of(directory: &Path) -> Option<String> {
+    filesystem_in_mounts(&fs::read_to_string("/proc/mounts").ok()?, directory)
+}
+
+/// Same as `filesystem_of` with the contents of `/proc/mounts` given.
+fn filesystem_in_mounts(mounts: &str, directory: &Path) -> Option<String> {
+    let mut filesystem: Option<(PathBuf, String)> = None;
+    for line in mounts.lines() {
+        // `device mount_point type options dump pass`
+        let mut fields = line.split_whitespace();
+        let (Some(_), Some(mount_point), Some(kind)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mount_point = PathBuf::from(unescape_mount_path(mount_point));
+        // Later mounts on the same point hide the earlier ones
+        let deeper = filesystem.as_ref().is_none_or(|(deepest, _)| {
+            mount_point.components().count() >= deepest.components().count()
+        });
+        if directory.starts_with(&mount_point) && deeper {
+            filesystem = Some((mount_point, kind.to_string()));
+        }
+    }
+    filesystem.map(|(_, kind)| kind)
+}
+
+/// Name of the whole disk of the device `major:minor`, the parent of a partition.
+fn block_device_of(major: u32, minor: u32) -> Option<String> {
+    let device = fs::canonicalize(format!("/sys/dev/block/{}:{}", major, minor)).ok()?;
+    let disk = if device.join("partition").exists() {
+        device.parent()?
+    } else {
+        &device
+    };
+    Some(disk.file_name()?.to_string_lossy().into_owned())
+}
+
+#[cfg(target_os = "linux")]
+fn device_numbers(directory: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = fs::metadata(directory).ok()?.dev();
+    Some((libc::major(device), libc::minor(device)))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn device_numbers(_directory: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+/// Filesystem and disk of `directory`.
+pub fn get_storage_info(directory: &Path) -> StorageInfo {
+    let Ok(directory) = fs::canonicalize(directory) else {
+        return StorageInfo::default();
+    };
+    let block_device =
+        device_numbers(&directory).and_then(|(major, minor)| block_device_of(major, minor));
+    let sysfs = |file: &str| {
+        let block_device = block_device.as_ref()?;
+        read_trimmed(&format!("/sys/block/{}/{}", block_device, file))
+    };
+    StorageInfo {
+        filesystem: filesystem_of(&directory),
+        model: sysfs("device/model"),
+        rotational: sysfs("queue/rotational").map(|rotational| rotational == "1"),
+        scheduler: sysfs("queue/scheduler").map(|value| selected_option(&value).to_string()),
+        block_device,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTS: &str = "\
+/dev/vda / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+tmpfs /tmp tmpfs rw,nosuid,nodev 0 0
+/dev/vdb /mnt/data xfs rw,relatime 0 0
+/dev/vdc /mnt/data/fast btrfs rw,relatime 0 0
+/dev/vdd /mnt/my\\040disk ext4 rw,relatime 0 0
+";
+
+    #[test]
+    fn unescapes_octal_sequences() {
+        assert_eq!(unescape_mount_path("/mnt/my\\040disk"), "/mnt/my disk");
+        assert_eq!(unescape_mount_path("/a\\011b\\012c"), "/a\tb\nc");
+        assert_eq!(unescape_mount_path("/back\\134slash"), "/back\\slash");
+        // An escaped backslash followed by digits isn't another escape
+        assert_eq!(unescape_mount_path("/x\\134040"), "/x\\040");
+        assert_eq!(unescape_mount_path("/plain"), "/plain");
+    }
+
+    #[test]
+    fn picks_the_deepest_mount_point() {
+        let filesystem = |path: &str| filesystem_in_mounts(MOUNTS, Path::new(path));
+        assert_eq!(filesystem("/home/user").as_deref(), Some("ext4"));
+        assert_eq!(filesystem("/mnt/data").as_deref(), Some("xfs"));
+        assert_eq!(filesystem("/mnt/data/fast/bench").as_deref(), Some("btrfs"));
+        // Components are compared, not string prefixes
+        assert_eq!(filesystem("/mnt/database").as_deref(), Some("ext4"));
+        assert_eq!(filesystem("/mnt/my disk/x").as_deref(), Some("ext4"));
+    }
+
+    #[test]
+    fn later_mounts_hide_earlier_ones() {
+        let mounts = "tmpfs /tmp tmpfs rw 0 0\n/dev/vde /tmp xfs rw 0 0\n";
+        assert_eq!(
+            filesystem_in_mounts(mounts, Path::new("/tmp/bench")).as_deref(),
+            Some("xfs")
+        );
+    }
+
+    #[test]
+    fn no_mount_point_means_no_filesystem() {
+        assert_eq!(filesystem_in_mounts("", Path::new("/tmp")), None);
+        assert_eq!(filesystem_in_mounts("garbage\n", Path::new("/tmp")), None);
+    }
+}
@@ -0,0 +1,367 @@
+use std::fs;
+use std::path::Path;
+
+// Signatures returned by `cpuid` leaf 0x40000000 mapped to the hypervisor name.
+const CPUID_HYPERVISORS: &[(&str, &str)] = &[
+    ("KVMKVMKVM", "KVM"),
+    ("Microsoft Hv", "Hyper-V"),
+    ("VMwareVMware", "VMware"),
+    ("XenVMMXenVMM", "Xen"),
+    ("TCGTCGTCGTCG", "QEMU"),
+    ("VBoxVBoxVBox", "VirtualBox"),
+    (" lrpepyh  vr", "Parallels"),
+    ("ACRNACRNACRN", "ACRN"),
+    ("bhyve bhyve ", "bhyve"),
+    ("QNXQVMBSQG", "QNX"),
+];
+
+// Substrings of the DMI vendor/product mapped to the hypervisor name.
+const DMI_HYPERVISORS: &[(&str, &str)] = &[
+    ("QEMU", "QEMU"),
+    ("KVM", "KVM"),
+    ("VMware", "VMware"),
+    ("VirtualBox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("Virtual Machine", "Hyper-V"),
+    ("Xen", "Xen"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("Google Compute Engine", "Google Compute Engine"),
+    ("Parallels", "Parallels"),
+    ("BHYVE", "bhyve"),
+];
+
+// Substrings of `/proc/1/cgroup` that reveal the container runtime.
+const CGROUP_RUNTIMES: &[(&str, &str)] = &[
+    ("kubepods", "kubernetes"),
+    ("docker", "docker"),
+    ("containerd", "containerd"),
+    ("libpod", "podman"),
+    ("lxc", "lxc"),
+];
+
+/// Hypervisor, container and cgroup CPU limits of the machine running the benchmark.
+#[derive(Debug)]
+pub struct VirtualizationInfo {
+    pub hypervisor: Option<String>,
+    pub container_runtime: Option<String>,
+    pub cgroup_version: Option<u8>,
+    /// CPUs allowed by the cgroup CPU bandwidth limit (`cpu.max` or `cpu.cfs_quota_us`).
+    pub cgroup_cpu_quota: Option<f64>,
+    /// CPUs allowed by the cgroup cpuset.
+    pub cgroup_cpuset_cpus: Option<usize>,
+    pub logical_cpus: usize,
+    /// Threads the benchmark can actually keep busy at the same time.
+    pub effective_parallelism: usize,
+}
+
+impl VirtualizationInfo {
+    pub fn is_virtualized(&self) -> bool {
+        self.hypervisor.is_some() || self.container_runtime.is_some()
+    }
+
+    pub fn is_constrained(&self) -> bool {
+        self.effective_parallelism < self.logical_cpus
+    }
+}
+
+fn read_trimmed(path: impl AsRef<Path>) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn hypervisor_from_cpuid() -> Option<String> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // SAFETY: leaf 1 is always available and leaf 0x40000000 is reserved for hypervisors,
+    // it is only queried when the "hypervisor present" bit is set.
+    let leaf1 = unsafe { __cpuid(1) };
+    if leaf1.ecx & (1 << 31) == 0 {
+        return None;
+    }
+    let leaf = unsafe { __cpuid(0x4000_0000) };
+
+    let mut signature = Vec::with_capacity(12);
+    for register in [leaf.ebx, leaf.ecx, leaf.edx] {
+        signature.extend_from_slice(&register.to_le_bytes());
+    }
+    Some(hypervisor_from_signature(&String::from_utf8_lossy(
+        &signature,
+    )))
+}
+
+/// Maps the `cpuid` hypervisor signature (which may be NUL padded) to a name.
+fn hypervisor_from_signature(signature: &str) -> String {
+    let signature = signature.trim_end_matches('\0');
+    CPUID_HYPERVISORS
+        .iter()
+        .find(|(known, _)| signature == *known)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Unknown ({})", signature.trim()))
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn hypervisor_from_cpuid() -> Option<String> {
+    None
+}
+
+fn hypervisor_from_system() -> Option<String> {
+    if let Some(kind) = read_trimmed("/sys/hypervisor/type") {
+        return Some(kind);
+    }
+
+    let dmi = ["sys_vendor", "product_name", "bios_vendor"]
+        .iter()
+        .filter_map(|file| read_trimmed(format!("/sys/class/dmi/id/{}", file)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    hypervisor_from_dmi(&dmi)
+}
+
+fn hypervisor_from_dmi(dmi: &str) -> Option<String> {
+    DMI_HYPERVISORS
+        .iter()
+        .find(|(needle, _)| dmi.contains(needle))
+        .map(|(_, name)| name.to_string())
+}
+
+fn detect_container_runtime() -> Option<String> {
+    if std::env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some("kubernetes".to_string());
+    }
+    if let Ok(container) = std::env::var("container") {
+        if !container.is_empty() {
+            return Some(container);
+        }
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+
+    runtime_from_cgroup(&fs::read_to_string("/proc/1/cgroup").ok()?)
+}
+
+fn runtime_from_cgroup(cgroup: &str) -> Option<String> {
+    CGROUP_RUNTIMES
+        .iter()
+        .find(|(needle, _)| cgroup.contains(needle))
+        .map(|(_, name)| name.to_string())
+}
+
+/// Counts the CPUs in a cpuset list such as `0-3,8,10-11`.
+fn count_cpu_list(list: &str) -> Option<usize> {
+    let mut count = 0;
+    for range in list.split(',').filter(|range| !range.is_empty()) {
+        count += match range.split_once('-') {
+            Some((start, end)) => {
+                end.parse::<usize>()
+                    .ok()?
+                    .checked_sub(start.parse::<usize>().ok()?)?
+                    + 1
+            }
+            None => {
+                range.parse::<usize>().ok()?;
+                1
+            }
+        };
+    }
+    if count > 0 {
+        Some(count)
+    } else {
+        None
+    }
+}
+
+fn quota_to_cpus(quota: i64, period: i64) -> Option<f64> {
+    if quota > 0 && period > 0 {
+        Some(quota as f64 / period as f64)
+    } else {
+        None
+    }
+}
+
+/// Parses a cgroup v2 `cpu.max` such as `150000 100000`; `max` means no limit.
+fn parse_cpu_max(cpu_max: &str) -> Option<f64> {
+    let (quota, period) = cpu_max.split_once(' ')?;
+    quota_to_cpus(quota.parse().ok()?, period.parse().ok()?)
+}
+
+/// Path of the cgroup v2 (the `0::` entry) in the contents of `/proc/self/cgroup`.
+fn cgroup_v2_path(content: &str) -> Option<&str> {
+    content.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/// Directory of the cgroup v2 the process belongs to, falling back to the root
+/// (which is what containers see thanks to the cgroup namespace).
+fn cgroup_v2_dirs() -> Vec<String> {
+    let mut dirs = Vec::new();
+    if let Ok(content) = fs::read_to_string("/proc/self/cgroup") {
+        if let Some(path) = cgroup_v2_path(&content).filter(|path| *path != "/") {
+            dirs.push(format!("/sys/fs/cgroup{}", path));
+        }
+    }
+    dirs.push("/sys/fs/cgroup".to_string());
+    dirs
+}
+
+fn detect_cgroup_limits() -> (Option<u8>, Option<f64>, Option<usize>) {
+    if Path::new("/sys/fs/cgroup/cgroup.controllers").exists() {
+        let dirs = cgroup_v2_dirs();
+        let quota = dirs
+            .iter()
+            .find_map(|dir| parse_cpu_max(&read_trimmed(format!("{}/cpu.max", dir))?));
+        let cpuset = dirs.iter().find_map(|dir| {
+            count_cpu_list(&read_trimmed(format!("{}/cpuset.cpus.effective", dir))?)
+        });
+        return (Some(2), quota, cpuset);
+    }
+
+    if Path::new("/sys/fs/cgroup/cpu").exists() || Path::new("/sys/fs/cgroup/cpuset").exists() {
+        let quota = read_trimmed("/sys/fs/cgroup/cpu/cpu.cfs_quota_us")
+            .and_then(|quota| quota.parse().ok())
+            .zip(
+                read_trimmed("/sys/fs/cgroup/cpu/cpu.cfs_period_us")
+                    .and_then(|period| period.parse().ok()),
+            )
+            .and_then(|(quota, period)| quota_to_cpus(quota, period));
+        let cpuset = read_trimmed("/sys/fs/cgroup/cpuset/cpuset.cpus")
+            .and_then(|list| count_cpu_list(&list));
+        return (Some(1), quota, cpuset);
+    }
+
+    (None, None, None)
+}
+
+pub fn get_virtualization_info() -> VirtualizationInfo {
+    // `num_cpus::get()` already honours some limits, so the online CPU list is preferred.
+    let logical_cpus = read_trimmed("/sys/devices/system/cpu/online")
+        .and_then(|list| count_cpu_list(&list))
+        .unwrap_or_else(num_cpus::get);
+
+    let (cgroup_version, cgroup_cpu_quota, cgroup_cpuset_cpus) = detect_cgroup_limits();
+
+    // The affinity mask (e.g. `taskset`) also limits the usable CPUs.
+    let available = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(logical_cpus);
+
+    VirtualizationInfo {
+        hypervisor: hypervisor_from_cpuid().or_else(hypervisor_from_system),
+        container_runtime: detect_container_runtime(),
+        cgroup_version,
+        cgroup_cpu_quota,
+        cgroup_cpuset_cpus,
+        logical_cpus,
+        effective_parallelism: effective_parallelism(
+            available,
+            logical_cpus,
+            cgroup_cpu_quota,
+            cgroup_cpuset_cpus,
+        ),
+    }
+}
+
+/// Smallest of the affinity mask, the online CPUs and the cgroup limits, never below one.
+fn effective_parallelism(
+    available: usize,
+    logical_cpus: usize,
+    cpu_quota: Option<f64>,
+    cpuset_cpus: Option<usize>,
+) -> usize {
+    let mut parallelism = available.min(logical_cpus);
+    if let Some(quota) = cpu_quota {
+        parallelism = parallelism.min(quota.ceil() as usize);
+    }
+    if let Some(cpuset) = cpuset_cpus {
+        parallelism = parallelism.min(cpuset);
+    }
+    parallelism.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_cpu_lists() {
+        assert_eq!(count_cpu_list("0"), Some(1));
+        assert_eq!(count_cpu_list("0-3"), Some(4));
+        assert_eq!(count_cpu_list("0-3,8,10-11"), Some(7));
+        assert_eq!(count_cpu_list(""), None);
+        assert_eq!(count_cpu_list("3-1"), None);
+        assert_eq!(count_cpu_list("0-a"), None);
+    }
+
+    #[test]
+    fn parses_cgroup_v2_cpu_max() {
+        assert_eq!(parse_cpu_max("150000 100000"), Some(1.5));
+        assert_eq!(parse_cpu_max("max 100000"), None);
+        assert_eq!(parse_cpu_max("0 100000"), None);
+        assert_eq!(parse_cpu_max("100000"), None);
+    }
+
+    #[test]
+    fn cgroup_v1_quota_is_disabled_when_negative() {
+        assert_eq!(quota_to_cpus(200_000, 100_000), Some(2.0));
+        assert_eq!(quota_to_cpus(-1, 100_000), None);
+        assert_eq!(quota_to_cpus(100_000, 0), None);
+    }
+
+    #[test]
+    fn finds_the_cgroup_v2_path() {
+        let hybrid = "12:cpuset:/\n1:name=systemd:/user.slice\n0::/user.slice/session-2.scope\n";
+        assert_eq!(cgroup_v2_path(hybrid), Some("/user.slice/session-2.scope"));
+        assert_eq!(cgroup_v2_path("0::/\n"), Some("/"));
+        assert_eq!(cgroup_v2_path("4:cpu,cpuacct:/docker/abc\n"), None);
+    }
+
+    #[test]
+    fn detects_container_runtimes_from_cgroups() {
+        let docker = "12:cpu,cpuacct:/docker/0123456789abcdef\n";
+        assert_eq!(runtime_from_cgroup(docker).as_deref(), Some("docker"));
+        let kubernetes = "0::/kubepods/burstable/pod1/cri-containerd-abc.scope\n";
+        assert_eq!(
+            runtime_from_cgroup(kubernetes).as_deref(),
+            Some("kubernetes")
+        );
+        assert_eq!(runtime_from_cgroup("0::/init.scope\n"), None);
+    }
+
+    #[test]
+    fn detects_hypervisors() {
+        assert_eq!(hypervisor_from_signature("KVMKVMKVM\0\0\0"), "KVM");
+        assert_eq!(hypervisor_from_signature("Microsoft Hv"), "Hyper-V");
+        assert_eq!(hypervisor_from_signature(" lrpepyh  vr"), "Parallels");
+        assert_eq!(
+            hypervisor_from_signature("NewVisor\0\0\0\0"),
+            "Unknown (NewVisor)"
+        );
+        assert_eq!(
+            hypervisor_from_dmi("innotek GmbH VirtualBox").as_deref(),
+            Some("VirtualBox")
+        );
+        assert_eq!(
+            hypervisor_from_dmi("Microsoft Corporation Virtual Machine").as_deref(),
+            Some("Hyper-V")
+        );
+        assert_eq!(hypervisor_from_dmi("Dell Inc. PowerEdge R740"), None);
+    }
+
+    #[test]
+    fn effective_parallelism_takes_the_tightest_limit() {
+        assert_eq!(effective_parallelism(8, 8, None, None), 8);
+        assert_eq!(effective_parallelism(4, 8, None, None), 4);
+        assert_eq!(effective_parallelism(8, 8, Some(1.5), None), 2);
+        assert_eq!(effective_parallelism(8, 8, Some(4.0), Some(3)), 3);
+        assert_eq!(effective_parallelism(8, 8, Some(0.1), None), 1);
+    }
+}
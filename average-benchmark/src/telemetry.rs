@@ -0,0 +1,74 @@
+use serde::Serialize;
+use std::time::Duration;
+use sysinfo::{Components, System};
+
+use crate::benchmark::Workload;
+
+/// State of the machine right after one workload run.
+#[derive(Debug, Serialize)]
+pub struct TelemetrySample {
+    pub workload: Workload,
+    pub repetition: u32,
+    pub duration_ms: u128,
+    /// Average current frequency of all the CPUs.
+    pub cpu_frequency_mhz: Option<u64>,
+    /// Hottest sensor reported by the system.
+    pub temperature_c: Option<f32>,
+}
+
+pub struct Telemetry {
+    system: System,
+    components: Components,
+    pub samples: Vec<TelemetrySample>,
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Telemetry::new()
+    }
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Telemetry {
+            system: System::new(),
+            components: Components::new_with_refreshed_list(),
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn sample(
+        &mut self,
+        workload: Workload,
+        repetition: u32,
+        duration: Duration,
+    ) -> &TelemetrySample {
+        self.system.refresh_cpu_frequency();
+        let frequencies: Vec<u64> = self
+            .system
+            .cpus()
+            .iter()
+            .map(|cpu| cpu.frequency())
+            .filter(|frequency| *frequency > 0)
+            .collect();
+        let cpu_frequency_mhz = (!frequencies.is_empty())
+            .then(|| frequencies.iter().sum::<u64>() / frequencies.len() as u64);
+
+        self.components.refresh(false);
+        let temperature_c = self
+            .components
+            .iter()
+            .filter_map(|component| component.temperature())
+            .filter(|temperature| temperature.is_finite())
+            .reduce(f32::max);
+
+        self.samples.push(TelemetrySample {
+            workload,
+            repetition,
+            duration_ms: duration.as_millis(),
+            cpu_frequency_mhz,
+            temperature_c,
+        });
+        self.samples.last().expect("sample just pushed")
+    }
+}
@@ -1,28 +1,38 @@
-use std::io;
-
-pub fn ask_to_send() -> bool {
-    println!("¿Desea enviar los datos de la prueba al servidor? (y/n)");
-    println!("(Tu información solo se usará para generar un reporte en Discord y no se almacenará en ninguna base de datos.)");
-    let mut respuesta: String = String::new();
-
-    loop {
-        match io::stdin().read_line(&mut respuesta) {
-            Ok(_) => {
-                let input = respuesta.trim().to_lowercase();
-                if input == "y" {
-                    return true;
-                } else if input == "n" {
-                    return false;
-                } else {
-                    println!("Entrada no válida. Por favor, presione 'y' para sí o 'n' para no.");
-                    respuesta.clear();
-                }
-            }
-            Err(_) => {
-                println!("Error al leer la entrada. Intente nuevamente.");
-                respuesta.clear();
-            }
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use average_benchmark::i18n::t;
+
+static MESSAGES_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Sends the messages printed with `message!` to stderr, so with the JSON and HTML
+/// outputs stdout only has the payload.
+pub fn set_messages_to_stderr(to_stderr: bool) {
+    MESSAGES_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
+pub fn messages_to_stderr() -> bool {
+    MESSAGES_TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// `println!` for the messages that aren't part of the output, such as prompts and the
+/// outcome of sending the results.
+macro_rules! message {
+    ($($arg:tt)*) => {
+        if $crate::utils::messages_to_stderr() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
         }
+    };
+}
+pub(crate) use message;
+
+pub fn display_payload_preview(payload: &Value) {
+    println!("\x1B[34m{}\x1B[0m", t!("payload-preview"));
+    match serde_json::to_string_pretty(payload) {
+        Ok(json) => println!("{}", json),
+        Err(_) => println!("{}", payload),
     }
 }
 
@@ -42,9 +52,6 @@ pub fn display_banner() {
 }
 
 pub fn display_privacy_policy() {
-    println!("\x1B[34m{}\x1B[0m", "ℹ️  Esta aplicación enviará información sobre tu CPU y sistema operativo a un webhook de Discord.");
-    println!(
-        "\x1B[34m{}\x1B[0m",
-        "📜 Puedes revisar los detalles en el archivo PRIVACY.md en el repositorio."
-    )
+    println!("\x1B[34m{}\x1B[0m", t!("privacy-policy-notice"));
+    println!("\x1B[34m{}\x1B[0m", t!("privacy-policy-details"));
 }
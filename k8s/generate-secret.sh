@@ -35,6 +35,7 @@ metadata:
 type: Opaque
 stringData:
   DISCORD_WEBHOOK_URL: "$DISCORD_WEBHOOK_URL"
+  SIGNING_PUBLIC_KEYS: "$SIGNING_PUBLIC_KEYS"
 EOF
 
 echo "✅ Secreto generado correctamente en $SECRET_FILE"